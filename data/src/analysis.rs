@@ -0,0 +1,296 @@
+//! Detect samples that are most likely static noise.
+//!
+//! Corrupt modules, and samples that are compressed in a way that isn't supported,
+//! produce samples that are just random data. They are recognised by:
+//!
+//! * Spectral flatness - random data has the same energy at every frequency.
+//! * Zero crossing rate - random data changes sign about every other frame.
+//! * Entropy - random data uses every amplitude equally often,
+//!   whereas real audio mostly stays close to zero.
+//!
+//! Noisy instruments such as hi-hats have a flat spectrum too,
+//! but they decay, so their amplitudes are nowhere near as evenly spread.
+
+use std::f32::consts::PI;
+
+/// Samples that are shorter than this can't be judged
+const MIN_FRAMES: usize = 256;
+
+/// Size of the blocks that the spectrum is measured over
+const BLOCK_SIZE: usize = 1024;
+
+/// At most this many blocks are measured, spread evenly over the sample
+const MAX_BLOCKS: usize = 32;
+
+/// Amplitudes are sorted into this many bins to measure entropy
+const BINS: usize = 256;
+
+/// A sample is noise if every measurement is above its threshold
+const FLATNESS_THRESHOLD: f32 = 0.35;
+const ZERO_CROSSING_THRESHOLD: f32 = 0.3;
+const ENTROPY_THRESHOLD: f32 = 0.9;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Analysis {
+    /// From 0 (a pure tone) to 1 (white noise)
+    pub flatness: f32,
+    /// Sign changes per frame, from 0 to 1
+    pub zero_crossing_rate: f32,
+    /// Entropy of the amplitudes, from 0 to 1
+    pub entropy: f32,
+}
+
+impl Analysis {
+    /// Analyse a sample, each channel is a separate buffer.
+    ///
+    /// Returns ``None`` if the sample is too short.
+    pub fn new(channels: &[Vec<f32>]) -> Option<Self> {
+        let mono = mix(channels);
+
+        if mono.len() < MIN_FRAMES {
+            return None;
+        }
+
+        Some(Self {
+            flatness: flatness(&mono),
+            zero_crossing_rate: zero_crossing_rate(&mono),
+            entropy: entropy(&mono),
+        })
+    }
+
+    pub fn is_noise(&self) -> bool {
+        self.flatness > FLATNESS_THRESHOLD
+            && self.zero_crossing_rate > ZERO_CROSSING_THRESHOLD
+            && self.entropy > ENTROPY_THRESHOLD
+    }
+}
+
+/// Is the sample most likely static noise?
+pub fn is_noise(channels: &[Vec<f32>]) -> bool {
+    Analysis::new(channels).is_some_and(|analysis| analysis.is_noise())
+}
+
+fn mix(channels: &[Vec<f32>]) -> Vec<f32> {
+    match channels {
+        [] => Vec::new(),
+        [mono] => mono.clone(),
+        [first, rest @ ..] => first
+            .iter()
+            .enumerate()
+            .map(|(frame, sample)| {
+                let sum: f32 = rest.iter().filter_map(|ch| ch.get(frame)).sum();
+                (sample + sum) / channels.len() as f32
+            })
+            .collect(),
+    }
+}
+
+fn zero_crossing_rate(buffer: &[f32]) -> f32 {
+    let crossings = buffer
+        .windows(2)
+        .filter(|pair| (pair[0] >= 0.0) != (pair[1] >= 0.0))
+        .count();
+
+    crossings as f32 / (buffer.len() - 1) as f32
+}
+
+/// Shannon entropy of the amplitudes, relative to the highest possible entropy
+fn entropy(buffer: &[f32]) -> f32 {
+    let mut histogram = [0usize; BINS];
+
+    for sample in buffer {
+        let bin = ((sample.clamp(-1.0, 1.0) + 1.0) / 2.0 * (BINS - 1) as f32).round();
+        histogram[bin as usize] += 1;
+    }
+
+    let total = buffer.len() as f32;
+
+    let entropy: f32 = histogram
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f32 / total;
+            -p * p.log2()
+        })
+        .sum();
+
+    entropy / (BINS as f32).log2()
+}
+
+/// The ratio between the geometric and arithmetic mean of the power spectrum,
+/// averaged over a number of blocks.
+fn flatness(buffer: &[f32]) -> f32 {
+    let size = match buffer.len() {
+        len if len >= BLOCK_SIZE => BLOCK_SIZE,
+        len => prev_power_of_two(len),
+    };
+
+    let blocks = (buffer.len() / size).clamp(1, MAX_BLOCKS);
+    let step = match blocks {
+        1 => 0,
+        n => (buffer.len() - size) / (n - 1),
+    };
+
+    let window: Vec<f32> = (0..size)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / size as f32).cos())
+        .collect();
+
+    let mut total = 0.0;
+    let mut measured = 0;
+
+    for block in 0..blocks {
+        let start = block * step;
+
+        let mut re: Vec<f32> = buffer[start..start + size]
+            .iter()
+            .zip(&window)
+            .map(|(sample, window)| sample * window)
+            .collect();
+        let mut im = vec![0.0; size];
+
+        fft(&mut re, &mut im);
+
+        // Ignore DC, and silent blocks
+        let power: Vec<f32> = (1..size / 2)
+            .map(|bin| re[bin] * re[bin] + im[bin] * im[bin] + f32::EPSILON)
+            .collect();
+
+        let arithmetic = power.iter().sum::<f32>() / power.len() as f32;
+
+        if arithmetic <= f32::EPSILON * 2.0 {
+            continue;
+        }
+
+        let geometric = (power.iter().map(|p| p.ln()).sum::<f32>() / power.len() as f32).exp();
+
+        total += geometric / arithmetic;
+        measured += 1;
+    }
+
+    match measured {
+        0 => 0.0,
+        n => total / n as f32,
+    }
+}
+
+fn prev_power_of_two(n: usize) -> usize {
+    match n.is_power_of_two() {
+        true => n,
+        false => n.next_power_of_two() / 2,
+    }
+}
+
+/// In place radix-2 FFT. The length must be a power of two.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+
+    // Bit reversal
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f32;
+
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let a = start + k;
+                let b = a + len / 2;
+
+                let tr = re[b] * cos - im[b] * sin;
+                let ti = re[b] * sin + im[b] * cos;
+
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: f32 = 44100.0;
+    const FRAMES: usize = 44100;
+
+    /// Uniform white noise from -1 to 1, using a fixed seed so the tests are repeatable.
+    fn white_noise(frames: usize) -> Vec<f32> {
+        let mut state: u32 = 0x2545_F491;
+
+        (0..frames)
+            .map(|_| {
+                // xorshift32
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as f32 / u32::MAX as f32 * 2.0 - 1.0
+            })
+            .collect()
+    }
+
+    fn sine(frequency: f32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|frame| (2.0 * PI * frequency * frame as f32 / RATE).sin() * 0.8)
+            .collect()
+    }
+
+    #[test]
+    fn white_noise_is_noise() {
+        assert!(is_noise(&[white_noise(FRAMES)]));
+    }
+
+    #[test]
+    fn stereo_white_noise_is_noise() {
+        assert!(is_noise(&[white_noise(FRAMES), white_noise(FRAMES)]));
+    }
+
+    #[test]
+    fn sine_is_not_noise() {
+        let analysis = Analysis::new(&[sine(440.0, FRAMES)]).unwrap();
+
+        assert!(!analysis.is_noise());
+        assert!(analysis.flatness < FLATNESS_THRESHOLD);
+        assert!(analysis.zero_crossing_rate < ZERO_CROSSING_THRESHOLD);
+    }
+
+    #[test]
+    fn hi_hat_is_not_noise() {
+        // A hi-hat is mostly noise, but it fades out quickly.
+        let decay = 0.05 * RATE;
+
+        let hi_hat: Vec<f32> = white_noise(FRAMES / 4)
+            .into_iter()
+            .enumerate()
+            .map(|(frame, sample)| sample * (-(frame as f32) / decay).exp())
+            .collect();
+
+        let analysis = Analysis::new(&[hi_hat]).unwrap();
+
+        assert!(!analysis.is_noise());
+        assert!(analysis.entropy < ENTROPY_THRESHOLD);
+    }
+
+    #[test]
+    fn short_samples_are_not_judged() {
+        assert_eq!(Analysis::new(&[white_noise(MIN_FRAMES - 1)]), None);
+        assert!(!is_noise(&[]));
+    }
+}
@@ -2,14 +2,17 @@ use std::{fs, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+pub mod filters;
 pub mod general;
 pub mod name_params;
+pub mod processing;
 pub mod sample_naming;
 pub mod sample_ripping;
-// pub mod filters;
 
+pub use filters::FilterConfig;
 pub use general::GeneralConfig;
 pub use name_params::SampleNameParams;
+pub use processing::ProcessingConfig;
 pub use sample_naming::SampleNameConfig;
 pub use sample_ripping::SampleRippingConfig;
 
@@ -31,6 +34,7 @@ pub struct Config {
     pub general: GeneralConfig,
     pub ripping: SampleRippingConfig,
     pub naming: SampleNameConfig,
+    pub filters: FilterConfig,
 }
 
 impl Config {
@@ -1,13 +1,85 @@
 use std::path::Path;
 
+use serde::{Deserialize, Serialize};
+
 pub mod date;
 pub mod name;
 pub mod size;
 pub mod regex;
 
+pub use date::Date;
 pub use size::Size;
 pub use name::Name;
+pub use self::regex::Regex;
+
+pub trait Filter: Send + Sync {
+    /// ``root`` is the folder that was scanned to find ``path``.
+    fn matches(&self, path: &Path, root: &Path) -> bool;
+}
+
+/// A chain of filters. A path must satisfy every filter.
+#[derive(Default)]
+pub struct Filters(Vec<Box<dyn Filter>>);
+
+impl Filters {
+    pub fn new(filters: Vec<Box<dyn Filter>>) -> Self {
+        Self(filters)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Filter for Filters {
+    fn matches(&self, path: &Path, root: &Path) -> bool {
+        self.0.iter().all(|filter| filter.matches(path, root))
+    }
+}
+
+/// User defined filters to decide what files should be ripped
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct FilterConfig {
+    pub enable_date: bool,
+    pub date: Date,
+    pub enable_size: bool,
+    pub size: Size,
+    pub enable_name: bool,
+    pub name: Name,
+    pub enable_regex: bool,
+    pub regex: Regex,
+}
+
+impl FilterConfig {
+    /// Combine the enabled filters.
+    ///
+    /// Fails if the regex filter is enabled and its patterns don't compile,
+    /// otherwise every file would be ripped.
+    pub fn build(&self) -> Result<Filters, String> {
+        let mut filters: Vec<Box<dyn Filter>> = Vec::new();
+
+        if self.enable_date {
+            filters.push(Box::new(self.date.clone()));
+        }
+
+        if self.enable_size {
+            filters.push(Box::new(self.size));
+        }
+
+        if self.enable_name {
+            filters.push(Box::new(self.name.clone()));
+        }
+
+        if self.enable_regex {
+            let regex = self
+                .regex
+                .compile()
+                .map_err(|error| format!("Invalid regex filter: {error}"))?;
+
+            filters.push(Box::new(regex));
+        }
 
-pub trait Filter {
-    fn matches(&self, path: &Path) -> bool;
+        Ok(Filters::new(filters))
+    }
 }
@@ -1,26 +1,92 @@
-use std::{fs::metadata, str::FromStr};
+use std::fs::Metadata;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
 
-use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+use super::Filter;
+
+pub use chrono::NaiveDate;
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 pub struct Date {
     pub condition: Condition,
+    pub range: Range,
     pub before: Option<NaiveDate>,
     pub after: Option<NaiveDate>,
+    /// Used by [Range::LastDays]
+    pub days: u32,
 }
 
 impl Date {
     pub fn before(&self) -> NaiveDate {
-        // NaiveDate
-        self.before
-            .unwrap_or_else(|| chrono::Utc::now().naive_utc().date())
+        self.before.unwrap_or_else(|| Local::now().date_naive())
     }
 
     pub fn after(&self) -> NaiveDate {
         self.after
             .unwrap_or_else(|| NaiveDate::from_ymd_opt(1987, 1, 1).expect("A valid date"))
     }
+
+    /// Parse a date written as YYYY-MM-DD
+    pub fn parse(input: &str) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d").ok()
+    }
+
+    /// The time of the file that will be compared.
+    ///
+    /// Not every platform (or filesystem) records when a file was created,
+    /// so the modified time is used instead.
+    pub fn time(&self, metadata: &Metadata) -> Option<SystemTime> {
+        match self.condition {
+            Condition::Created => metadata.created().or_else(|_| metadata.modified()),
+            Condition::Modified => metadata.modified(),
+        }
+        .ok()
+    }
+
+    fn contains(&self, time: SystemTime) -> bool {
+        match self.range {
+            Range::Between => {
+                let date = DateTime::<Local>::from(time).date_naive();
+                (self.after()..=self.before()).contains(&date)
+            }
+            Range::LastDays => {
+                let period = Duration::from_secs(self.days as u64 * SECONDS_PER_DAY);
+
+                match SystemTime::now().checked_sub(period) {
+                    Some(since) => time >= since,
+                    None => true,
+                }
+            }
+        }
+    }
+}
+
+impl Filter for Date {
+    fn matches(&self, path: &Path, _root: &Path) -> bool {
+        let Ok(metadata) = path.metadata() else {
+            return false;
+        };
+
+        self.time(&metadata).is_some_and(|time| self.contains(time))
+    }
+}
+
+impl Default for Date {
+    fn default() -> Self {
+        Self {
+            condition: Condition::default(),
+            range: Range::default(),
+            before: None,
+            after: None,
+            days: 7,
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -46,3 +112,29 @@ impl std::fmt::Display for Condition {
         )
     }
 }
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Range {
+    /// Between two dates (inclusive)
+    #[default]
+    Between,
+    /// Within the last N days
+    LastDays,
+}
+
+impl Range {
+    pub const ALL: &'static [Self] = &[Self::Between, Self::LastDays];
+}
+
+impl std::fmt::Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Range::Between => "Between",
+                Range::LastDays => "In the last",
+            }
+        )
+    }
+}
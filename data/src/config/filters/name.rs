@@ -3,34 +3,75 @@ use std::path::Path;
 use super::Filter;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
 pub struct Name {
     pub contains: Vec<String>,
     pub starts_with: Vec<String>,
     pub ends_with: Vec<String>,
     pub extensions: Vec<String>,
+    pub case_sensitive: bool,
+    pub outcome: Outcome,
 }
 
-impl Default for Name {
-    fn default() -> Self {
-        Self {
-            contains: (0..30).into_iter().map(|f| format!("test{f}")).collect(),
-            starts_with: ["test", "test2", "test3"].into_iter().map(String::from).collect(),
-            ends_with: (0..50).into_iter().map(|f| f.to_string()).collect(),
-            extensions: (0..10).into_iter().map(|f| f.to_string()).collect(),
-        }
+impl Name {
+    pub fn is_empty(&self) -> bool {
+        self.contains.is_empty()
+            && self.starts_with.is_empty()
+            && self.ends_with.is_empty()
+            && self.extensions.is_empty()
     }
 }
 
 impl Filter for Name {
-    fn matches(&self, path: &Path) -> bool {
-        let filename = filename(path);
-        let extension = extension(path);
-
-        contains(&self.contains, |e| e.contains(filename))
-            && contains(&self.starts_with, |e| e.starts_with(filename))
-            && contains(&self.ends_with, |e| e.ends_with(filename))
-            && contains(&self.extensions, |e| e == extension)
+    fn matches(&self, path: &Path, _root: &Path) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        let case = |s: &str| match self.case_sensitive {
+            true => s.to_owned(),
+            false => s.to_lowercase(),
+        };
+
+        let filename = case(filename(path));
+        let stem = case(stem(path));
+        let extension = case(extension(path));
+
+        let matched = contains(&self.contains, |e| filename.contains(&case(e)))
+            && contains(&self.starts_with, |e| filename.starts_with(&case(e)))
+            && contains(&self.ends_with, |e| stem.ends_with(&case(e)))
+            && contains(&self.extensions, |e| extension == case(e.trim_start_matches('.')));
+
+        match self.outcome {
+            Outcome::Allow => matched,
+            Outcome::Reject => !matched,
+        }
+    }
+}
+
+/// What to do with a file that matches the filter
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    #[default]
+    Allow,
+    Reject,
+}
+
+impl Outcome {
+    pub const ALL: &'static [Self] = &[Self::Allow, Self::Reject];
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Allow => "Allow if it",
+                Self::Reject => "Reject if it",
+            }
+        )
     }
 }
 
@@ -44,6 +85,10 @@ fn filename(path: &Path) -> &str {
     path.file_name().and_then(|f| f.to_str()).unwrap_or_default()
 }
 
+fn stem(path: &Path) -> &str {
+    path.file_stem().and_then(|f| f.to_str()).unwrap_or_default()
+}
+
 fn extension(path: &Path) -> &str {
     path.extension().and_then(|f| f.to_str()).unwrap_or_default()
 }
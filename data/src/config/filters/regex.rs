@@ -1,7 +1,105 @@
+use std::path::Path;
+
 use super::Filter;
+use regex::{RegexSet, RegexSetBuilder};
 use serde::{Deserialize, Serialize};
 
+/// Include or exclude files with regular expressions.
+///
+/// A file is kept if it matches any of the ``include`` patterns (or there are none),
+/// and doesn't match any of the ``exclude`` patterns.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
 pub struct Regex {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub case_insensitive: bool,
+    pub target: Target,
+}
+
+impl Regex {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Check if a pattern is valid
+    pub fn validate(&self, pattern: &str) -> Result<(), String> {
+        regex::RegexBuilder::new(pattern)
+            .case_insensitive(self.case_insensitive)
+            .build()
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn compile(&self) -> Result<CompiledRegex, String> {
+        let build = |patterns: &[String]| {
+            RegexSetBuilder::new(patterns)
+                .case_insensitive(self.case_insensitive)
+                .build()
+                .map_err(|e| e.to_string())
+        };
+
+        Ok(CompiledRegex {
+            include: build(&self.include)?,
+            exclude: build(&self.exclude)?,
+            target: self.target,
+        })
+    }
+}
+
+/// What part of the path the patterns are matched against
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    #[default]
+    FileName,
+    /// The path relative to the scanned folder, using '/' as the separator
+    Path,
+}
+
+impl Target {
+    pub const ALL: &'static [Self] = &[Self::FileName, Self::Path];
+
+    fn haystack(self, path: &Path, root: &Path) -> String {
+        match self {
+            Target::FileName => path
+                .file_name()
+                .map(|f| f.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            Target::Path => path
+                .strip_prefix(root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/"),
+        }
+    }
+}
 
+impl std::fmt::Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Target::FileName => "File Name",
+                Target::Path => "Relative Path",
+            }
+        )
+    }
 }
 
+/// Regular expressions that are ready to be matched
+#[derive(Debug, Clone)]
+pub struct CompiledRegex {
+    include: RegexSet,
+    exclude: RegexSet,
+    target: Target,
+}
+
+impl Filter for CompiledRegex {
+    fn matches(&self, path: &Path, root: &Path) -> bool {
+        let haystack = self.target.haystack(path, root);
+
+        (self.include.is_empty() || self.include.is_match(&haystack))
+            && !self.exclude.is_match(&haystack)
+    }
+}
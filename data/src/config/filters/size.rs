@@ -23,13 +23,14 @@ impl Size {
 }
 
 impl Filter for Size {
-    fn matches(&self, path: &Path) -> bool {
+    fn matches(&self, path: &Path, _root: &Path) -> bool {
         let Ok(metadata) = path.metadata() else {
             return false;
         };
 
+        // A maximum of zero means there's no upper limit
         if self.max_as_bytes() == 0 {
-            self.min_as_bytes() >= metadata.len()
+            metadata.len() >= self.min_as_bytes()
         } else {
             (self.min_as_bytes()..=self.max_as_bytes()).contains(&metadata.len())
         }
@@ -0,0 +1,154 @@
+//! Processing that's applied to samples before they're exported.
+//!
+//! Every step is disabled by default.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct ProcessingConfig {
+    /// Subtract the average of each channel
+    pub remove_dc_offset: bool,
+    /// Remove silence at the start and end of a sample. Loops are never trimmed.
+    pub trim_silence: bool,
+    /// Anything quieter than this is silence (dBFS)
+    pub silence_threshold: i8,
+    pub channels: Channels,
+    pub normalize: Normalize,
+    /// Level that samples are normalized to (dBFS)
+    pub normalize_level: i8,
+    pub fade_in: bool,
+    /// Looped samples don't fade out
+    pub fade_out: bool,
+    /// Length of a fade in milliseconds
+    pub fade_length: u16,
+    /// Resample to this rate (Hz). 0 keeps the rate of the sample.
+    pub sample_rate: u32,
+    pub bit_depth: BitDepth,
+    /// Add triangular (TPDF) dither when the bit depth is reduced
+    pub dither: bool,
+}
+
+impl Default for ProcessingConfig {
+    fn default() -> Self {
+        Self {
+            remove_dc_offset: false,
+            trim_silence: false,
+            silence_threshold: -60,
+            channels: Channels::default(),
+            normalize: Normalize::default(),
+            normalize_level: -1,
+            fade_in: false,
+            fade_out: false,
+            fade_length: 10,
+            sample_rate: 0,
+            bit_depth: BitDepth::default(),
+            dither: true,
+        }
+    }
+}
+
+impl ProcessingConfig {
+    /// Is there anything to do?
+    pub fn is_enabled(&self) -> bool {
+        self.remove_dc_offset
+            || self.trim_silence
+            || self.channels != Channels::Keep
+            || self.normalize != Normalize::Off
+            || self.fade_in
+            || self.fade_out
+            || self.sample_rate != 0
+            || self.bit_depth != BitDepth::Keep
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Normalize {
+    #[default]
+    Off,
+    /// The loudest point reaches the level
+    Peak,
+    /// The average loudness reaches the level, without clipping
+    Rms,
+}
+
+impl Normalize {
+    pub const ALL: &'static [Self] = &[Self::Off, Self::Peak, Self::Rms];
+}
+
+impl std::fmt::Display for Normalize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Normalize::Off => "Off",
+                Normalize::Peak => "Peak",
+                Normalize::Rms => "RMS",
+            }
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Channels {
+    #[default]
+    Keep,
+    /// Mix stereo samples down to mono
+    Mono,
+    /// Copy mono samples to both channels
+    Stereo,
+}
+
+impl Channels {
+    pub const ALL: &'static [Self] = &[Self::Keep, Self::Mono, Self::Stereo];
+}
+
+impl std::fmt::Display for Channels {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Channels::Keep => "Keep",
+                Channels::Mono => "Mono",
+                Channels::Stereo => "Stereo",
+            }
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BitDepth {
+    #[default]
+    Keep,
+    Eight,
+    Sixteen,
+}
+
+impl BitDepth {
+    pub const ALL: &'static [Self] = &[Self::Keep, Self::Eight, Self::Sixteen];
+
+    /// Returns ``None`` if the bit depth is kept
+    pub fn bits(&self) -> Option<u8> {
+        match self {
+            BitDepth::Keep => None,
+            BitDepth::Eight => Some(8),
+            BitDepth::Sixteen => Some(16),
+        }
+    }
+}
+
+impl std::fmt::Display for BitDepth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                BitDepth::Keep => "Keep",
+                BitDepth::Eight => "8-bit",
+                BitDepth::Sixteen => "16-bit",
+            }
+        )
+    }
+}
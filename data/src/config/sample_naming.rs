@@ -1,7 +1,12 @@
+pub mod template;
+
 use serde::{Deserialize, Serialize};
-use xmodits_lib::export::name::{SampleNamer, SampleNamerTrait};
+use xmodits_lib::export::name::{Context, SampleNamer, SampleNamerTrait};
+use xmodits_lib::Sample;
+
+pub use template::{Field, Template};
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(default)]
 pub struct SampleNameConfig {
     pub index_raw: bool,
@@ -11,6 +16,9 @@ pub struct SampleNameConfig {
     pub lower: bool,
     pub prefix: bool,
     pub prefer_filename: bool,
+    /// Name samples with a template instead
+    pub use_template: bool,
+    pub template: String,
 }
 
 impl Default for SampleNameConfig {
@@ -23,13 +31,48 @@ impl Default for SampleNameConfig {
             lower: false,
             prefix: false,
             prefer_filename: true,
+            use_template: false,
+            template: String::from("{index:02} - {name|filename}.{ext}"),
         }
     }
 }
 
 impl SampleNameConfig {
+    /// Returns true if the name of the module is included in the names of its samples
+    pub fn includes_module(&self) -> bool {
+        match self.use_template {
+            true => Template::parse(&self.template).is_ok_and(|template| {
+                template.contains(Field::Module) || template.contains(Field::ModuleFile)
+            }),
+            false => self.prefix,
+        }
+    }
+
+    /// Check that the template can be parsed if it's used
+    pub fn validate(&self) -> Result<(), String> {
+        if self.use_template {
+            Template::parse(&self.template)
+                .map_err(|error| format!("Invalid template: {error}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Falls back to the regular settings if the template is invalid,
+    /// so call [`validate`](Self::validate) first.
     #[allow(clippy::needless_update)]
     pub fn build_func(&self) -> Box<dyn SampleNamerTrait> {
+        if self.use_template {
+            match Template::parse(&self.template) {
+                Ok(template) => {
+                    return Box::new(move |smp: &Sample, ctx: &Context, index: usize| {
+                        template.name(smp, ctx, index)
+                    })
+                }
+                Err(error) => tracing::error!("Ignoring sample name template: {error}"),
+            }
+        }
+
         SampleNamer {
             index_only: self.index_only,
             index_padding: self.index_padding,
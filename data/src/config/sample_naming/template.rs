@@ -0,0 +1,334 @@
+//! Name samples with a format string, e.g. ``{module}/{index:03}_{name|filename}_{rate}Hz.{ext}``
+//!
+//! Placeholders are written as ``{field}``. If a field is empty, the next one after ``|`` is used.
+//! A field can be followed by ``:N`` to pad numbers with zeros, or ``:upper``/``:lower``.
+//! Use ``{{`` and ``}}`` for literal braces, and ``/`` to place samples in subfolders.
+
+use std::path::Path;
+
+use xmodits_lib::export::name::Context;
+use xmodits_lib::Sample;
+
+/// Characters that aren't allowed in a file name
+const RESERVED: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template(Vec<Token>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Literal(String),
+    Placeholder { fields: Vec<Field>, spec: Spec },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Spec {
+    None,
+    Pad(usize),
+    Upper,
+    Lower,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// Sample name
+    Name,
+    /// Sample filename, without its extension
+    Filename,
+    /// Sequential index, starting from 1
+    Index,
+    /// Index of the sample as stored in the module
+    RawIndex,
+    Rate,
+    Bits,
+    /// Length in bytes
+    Length,
+    /// Length in frames
+    Frames,
+    /// Offset of the sample data
+    Pointer,
+    /// "mono" or "stereo"
+    Channels,
+    /// "signed" or "unsigned"
+    Signed,
+    /// Loop type
+    Loop,
+    /// Module filename without its extension
+    Module,
+    /// Module filename
+    ModuleFile,
+    /// Extension of the module
+    ModuleExt,
+    /// Number of samples in the module
+    Total,
+    /// Highest raw index in the module
+    Highest,
+    /// Extension of the exported format
+    Ext,
+}
+
+impl Field {
+    pub const ALL: &'static [Self] = &[
+        Self::Name,
+        Self::Filename,
+        Self::Index,
+        Self::RawIndex,
+        Self::Rate,
+        Self::Bits,
+        Self::Length,
+        Self::Frames,
+        Self::Pointer,
+        Self::Channels,
+        Self::Signed,
+        Self::Loop,
+        Self::Module,
+        Self::ModuleFile,
+        Self::ModuleExt,
+        Self::Total,
+        Self::Highest,
+        Self::Ext,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Field::Name => "name",
+            Field::Filename => "filename",
+            Field::Index => "index",
+            Field::RawIndex => "raw_index",
+            Field::Rate => "rate",
+            Field::Bits => "bits",
+            Field::Length => "length",
+            Field::Frames => "frames",
+            Field::Pointer => "pointer",
+            Field::Channels => "channels",
+            Field::Signed => "signed",
+            Field::Loop => "loop",
+            Field::Module => "module",
+            Field::ModuleFile => "module_file",
+            Field::ModuleExt => "module_ext",
+            Field::Total => "total",
+            Field::Highest => "highest",
+            Field::Ext => "ext",
+        }
+    }
+
+    fn value(&self, smp: &Sample, ctx: &Context, index: usize) -> Value {
+        let source = ctx.source_path;
+
+        match self {
+            Field::Name => Value::Text(smp.name.trim().to_owned()),
+            Field::Filename => Value::Text(
+                smp.filename
+                    .as_deref()
+                    .map(|f| stem(f.trim()).to_owned())
+                    .unwrap_or_default(),
+            ),
+            Field::Index => Value::Number(index as u64 + 1),
+            Field::RawIndex => Value::Number(smp.index_raw() as u64),
+            Field::Rate => Value::Number(smp.rate as u64),
+            Field::Bits => Value::Number(smp.bits() as u64),
+            Field::Length => Value::Number(smp.length as u64),
+            Field::Frames => Value::Number(smp.length_frames() as u64),
+            Field::Pointer => Value::Number(smp.pointer as u64),
+            Field::Channels => Value::Text(
+                match smp.is_stereo() {
+                    true => "stereo",
+                    false => "mono",
+                }
+                .into(),
+            ),
+            Field::Signed => Value::Text(
+                match smp.is_signed() {
+                    true => "signed",
+                    false => "unsigned",
+                }
+                .into(),
+            ),
+            Field::Loop => Value::Text(format!("{:?}", smp.looping.kind()).to_lowercase()),
+            Field::Module => Value::Text(path_str(source, Path::file_stem)),
+            Field::ModuleFile => Value::Text(path_str(source, Path::file_name)),
+            Field::ModuleExt => Value::Text(path_str(source, Path::extension)),
+            Field::Total => Value::Number(ctx.total as u64),
+            Field::Highest => Value::Number(ctx.highest as u64),
+            Field::Ext => Value::Text(ctx.extension.to_owned()),
+        }
+    }
+}
+
+impl std::str::FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .find(|field| field.key() == s.trim())
+            .copied()
+            .ok_or_else(|| format!("Unknown placeholder '{s}'"))
+    }
+}
+
+enum Value {
+    Text(String),
+    Number(u64),
+}
+
+impl Value {
+    fn is_empty(&self) -> bool {
+        matches!(self, Value::Text(text) if text.is_empty())
+    }
+
+    fn format(self, spec: Spec) -> String {
+        match (self, spec) {
+            (Value::Number(n), Spec::Pad(width)) => format!("{n:0width$}"),
+            (Value::Number(n), _) => n.to_string(),
+            (Value::Text(text), Spec::Upper) => text.to_uppercase(),
+            (Value::Text(text), Spec::Lower) => text.to_lowercase(),
+            (Value::Text(text), _) => text,
+        }
+    }
+}
+
+impl Template {
+    /// Parse a format string
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut tokens = Vec::new();
+        let mut literal = String::new();
+        let mut chars = input.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err("Unmatched '}'. Use '}}' for a literal brace".into()),
+                '{' => {
+                    let rest = chars.as_str();
+                    let Some(end) = rest.find('}') else {
+                        return Err("Unclosed '{'".into());
+                    };
+
+                    if !literal.is_empty() {
+                        tokens.push(Token::Literal(std::mem::take(&mut literal)));
+                    }
+
+                    tokens.push(placeholder(&rest[..end])?);
+                    chars = rest[end + 1..].chars();
+                }
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(literal));
+        }
+
+        if tokens.is_empty() {
+            return Err("Template is empty".into());
+        }
+
+        Ok(Self(tokens))
+    }
+
+    /// Returns true if the template contains the given field
+    pub fn contains(&self, field: Field) -> bool {
+        self.0.iter().any(|token| match token {
+            Token::Placeholder { fields, .. } => fields.contains(&field),
+            Token::Literal(_) => false,
+        })
+    }
+
+    /// Name a sample.
+    ///
+    /// Values are sanitized so that only the template can create subfolders.
+    /// The extension is appended if the template doesn't have ``{ext}``.
+    pub fn name(&self, smp: &Sample, ctx: &Context, index: usize) -> String {
+        let mut output = String::new();
+
+        for token in &self.0 {
+            match token {
+                Token::Literal(literal) => output.push_str(literal),
+                Token::Placeholder { fields, spec } => {
+                    let value = fields
+                        .iter()
+                        .map(|field| field.value(smp, ctx, index))
+                        .find(|value| !value.is_empty());
+
+                    if let Some(value) = value {
+                        output.push_str(&sanitize(&value.format(*spec)));
+                    }
+                }
+            }
+        }
+
+        let mut name = output
+            .split(['/', '\\'])
+            .map(str::trim)
+            .filter(|part| !matches!(*part, "" | "." | ".."))
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if name.is_empty() {
+            name = (index + 1).to_string();
+        }
+
+        if !self.contains(Field::Ext) {
+            name.push('.');
+            name.push_str(ctx.extension);
+        }
+
+        name
+    }
+}
+
+fn placeholder(inner: &str) -> Result<Token, String> {
+    let (fields, spec) = match inner.split_once(':') {
+        Some((fields, spec)) => (fields, spec.trim()),
+        None => (inner, ""),
+    };
+
+    let spec = match spec {
+        "" => Spec::None,
+        "upper" => Spec::Upper,
+        "lower" => Spec::Lower,
+        width => match width.parse::<usize>() {
+            Ok(width) if width <= 16 => Spec::Pad(width),
+            _ => return Err(format!("Invalid format '{width}' in '{{{inner}}}'")),
+        },
+    };
+
+    let fields = fields
+        .split('|')
+        .map(str::parse)
+        .collect::<Result<Vec<Field>, String>>()?;
+
+    Ok(Token::Placeholder { fields, spec })
+}
+
+/// Replace characters that can't be used in a file name
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            c if RESERVED.contains(&c) || c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+fn stem(filename: &str) -> &str {
+    match filename.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => filename,
+    }
+}
+
+fn path_str(path: Option<&Path>, f: impl Fn(&Path) -> Option<&std::ffi::OsStr>) -> String {
+    path.and_then(f)
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
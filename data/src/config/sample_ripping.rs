@@ -2,7 +2,9 @@ pub use super::SampleNameConfig;
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use xmodits_lib::export::Format;
+
+use super::ProcessingConfig;
+use crate::ExportFormat;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(default)]
@@ -12,7 +14,25 @@ pub struct SampleRippingConfig {
     pub folder_max_depth: u8,
     pub strict: bool,
     pub worker_threads: usize,
-    pub exported_format: Format,
+    pub exported_format: ExportFormat,
+    /// What to do if a sample already exists
+    pub collision: Collision,
+    /// Replace characters that can't be used on Windows, FAT or exFAT
+    pub sanitize: bool,
+    /// What to do with samples that were already ripped
+    pub dedup: Dedup,
+    /// Store where a sample came from in the exported file (WAV, AIFF and FLAC)
+    pub embed_tags: bool,
+    /// Write an SFZ instrument for every module, next to its samples
+    pub sfz: bool,
+    /// Write a SoundFont 2 bank for every module, with a preset per sample
+    pub soundfont: bool,
+    /// Write a JSON index of every sample ripped from a module
+    pub manifest: bool,
+    /// Applied to every sample before it's exported
+    pub processing: ProcessingConfig,
+    /// What to do with samples that are most likely static noise
+    pub noise: Noise,
 }
 
 impl Default for SampleRippingConfig {
@@ -24,11 +44,118 @@ impl Default for SampleRippingConfig {
             strict: true,
             exported_format: Default::default(),
             worker_threads: 0,
+            collision: Collision::default(),
+            sanitize: false,
+            dedup: Dedup::default(),
+            embed_tags: false,
+            sfz: false,
+            soundfont: false,
+            manifest: false,
+            processing: ProcessingConfig::default(),
+            noise: Noise::default(),
         }
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Collision {
+    /// Replace the existing file
+    #[default]
+    Overwrite,
+    /// Keep the existing file
+    Skip,
+    /// Append a number to the new file, e.g. "kick_1.wav"
+    Rename,
+    /// Treat it as an error
+    Error,
+}
+
+impl Collision {
+    pub const ALL: &'static [Self] = &[Self::Overwrite, Self::Skip, Self::Rename, Self::Error];
+}
+
+impl std::fmt::Display for Collision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Collision::Overwrite => "Overwrite",
+                Collision::Skip => "Skip",
+                Collision::Rename => "Rename",
+                Collision::Error => "Error",
+            }
+        )
+    }
+}
+
 fn default_dir() -> PathBuf {
     let fallback = || std::env::current_dir().unwrap_or_default();
     dirs::download_dir().unwrap_or_else(fallback)
 }
+
+/// Identical samples are detected by hashing their PCM data.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Dedup {
+    /// Export every sample
+    #[default]
+    Off,
+    /// Don't export duplicates
+    Skip,
+    /// Hard link duplicates to the first copy
+    HardLink,
+    /// Don't export duplicates, but record them in a manifest
+    Manifest,
+}
+
+impl Dedup {
+    pub const ALL: &'static [Self] = &[Self::Off, Self::Skip, Self::HardLink, Self::Manifest];
+}
+
+impl std::fmt::Display for Dedup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Dedup::Off => "Off",
+                Dedup::Skip => "Skip",
+                Dedup::HardLink => "Hard Link",
+                Dedup::Manifest => "Manifest",
+            }
+        )
+    }
+}
+
+/// Samples are checked with [crate::analysis].
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Noise {
+    /// Export them like any other sample
+    #[default]
+    Keep,
+    /// Don't export them
+    Skip,
+    /// Export them to a separate folder
+    Quarantine,
+}
+
+impl Noise {
+    pub const ALL: &'static [Self] = &[Self::Keep, Self::Skip, Self::Quarantine];
+
+    /// Name of the folder that quarantined samples are placed in
+    pub const FOLDER: &'static str = "quarantine";
+}
+
+impl std::fmt::Display for Noise {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Noise::Keep => "Keep",
+                Noise::Skip => "Skip",
+                Noise::Quarantine => "Quarantine",
+            }
+        )
+    }
+}
@@ -0,0 +1,94 @@
+//! Formats that samples can be exported to.
+//!
+//! Extends the formats provided by ``xmodits_lib`` with compressed ones,
+//! and stores loop points and tags in the formats that support them.
+
+mod chunk;
+mod flac;
+mod loops;
+pub mod process;
+mod resample;
+pub mod sf2;
+pub mod sfz;
+pub mod tags;
+mod vorbis;
+
+use serde::{Deserialize, Serialize};
+use xmodits_lib::export::Format;
+use xmodits_lib::AudioTrait;
+
+pub use tags::Tags;
+
+/// Variants are serialized in upper case, so existing configurations can still be read.
+///
+/// Every variant exists in every build, so that a configuration saved by a build
+/// with more formats can still be read. Check [`is_available`](Self::is_available) before ripping.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ExportFormat {
+    #[default]
+    Wav,
+    Aiff,
+    Its,
+    S3i,
+    Iff,
+    Raw,
+    /// Lossless, keeps the bit depth of the sample
+    Flac,
+    /// Lossy, requires the ``vorbis`` feature
+    Vorbis,
+}
+
+impl ExportFormat {
+    pub const ALL: &'static [Self] = &[
+        Self::Wav,
+        Self::Aiff,
+        Self::Its,
+        Self::S3i,
+        Self::Iff,
+        Self::Raw,
+        Self::Flac,
+        #[cfg(feature = "vorbis")]
+        Self::Vorbis,
+    ];
+
+    /// Returns false if XMODITS was built without the encoder for this format
+    pub fn is_available(&self) -> bool {
+        match self {
+            Self::Vorbis => vorbis::AVAILABLE,
+            _ => true,
+        }
+    }
+
+    pub fn get_impl(&self) -> Box<dyn AudioTrait> {
+        match self {
+            Self::Wav => Box::new(loops::Looped::wav(Format::WAV.get_impl())),
+            Self::Aiff => Box::new(loops::Looped::aiff(Format::AIFF.get_impl())),
+            Self::Its => Format::ITS.get_impl(),
+            Self::S3i => Format::S3I.get_impl(),
+            Self::Iff => Format::IFF.get_impl(),
+            Self::Raw => Format::RAW.get_impl(),
+            Self::Flac => Box::new(flac::Flac),
+            Self::Vorbis => Box::new(vorbis::Vorbis),
+        }
+    }
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ExportFormat::Wav => "WAV",
+                ExportFormat::Aiff => "AIFF",
+                ExportFormat::Its => "ITS",
+                ExportFormat::S3i => "S3I",
+                ExportFormat::Iff => "8SVX",
+                ExportFormat::Raw => "RAW",
+                ExportFormat::Flac => "FLAC",
+                ExportFormat::Vorbis => "Vorbis",
+            }
+        )
+    }
+}
@@ -0,0 +1,53 @@
+//! Append chunks to exported RIFF (WAV) and AIFF files.
+
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Container {
+    /// RIFF WAVE, sizes are little endian
+    Wav,
+    /// AIFF, sizes are big endian
+    Aiff,
+}
+
+impl Container {
+    /// Append a chunk to a complete file, and update the size in its header.
+    pub fn append(self, file: &mut Vec<u8>, id: &[u8; 4], data: &[u8]) -> io::Result<()> {
+        let magic = match self {
+            Self::Wav => b"RIFF",
+            Self::Aiff => b"FORM",
+        };
+
+        if file.len() < 12 || !file.starts_with(magic) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Unexpected file header",
+            ));
+        }
+
+        // Chunks start on an even offset, the last one may be missing its pad byte.
+        if file.len() % 2 == 1 {
+            file.push(0);
+        }
+
+        file.extend(id);
+        file.extend(self.size(data.len()));
+        file.extend(data);
+
+        if data.len() % 2 == 1 {
+            file.push(0);
+        }
+
+        let size = self.size(file.len() - 8);
+        file[4..8].copy_from_slice(&size);
+
+        Ok(())
+    }
+
+    fn size(self, size: usize) -> [u8; 4] {
+        match self {
+            Self::Wav => (size as u32).to_le_bytes(),
+            Self::Aiff => (size as u32).to_be_bytes(),
+        }
+    }
+}
@@ -0,0 +1,293 @@
+//! Lossless FLAC encoder.
+//!
+//! Samples keep their bit depth (8 or 16 bits).
+//! Each block is encoded with whichever fixed predictor leaves the smallest Rice coded residual.
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use xmodits_lib::{AudioTrait, Error, Sample};
+
+/// Samples per channel in a frame
+const BLOCK_SIZE: usize = 4096;
+
+/// Highest sample rate that FLAC can store
+const MAX_RATE: u32 = 655_350;
+
+/// Highest fixed predictor order
+const MAX_ORDER: usize = 4;
+
+/// Rice parameters above this need the escape code, so they're never picked
+const MAX_RICE_PARAM: u32 = 14;
+
+pub struct Flac;
+
+impl AudioTrait for Flac {
+    fn extension(&self) -> &str {
+        "flac"
+    }
+
+    fn write(&self, smp: &Sample, pcm: Cow<[u8]>, writer: &mut dyn Write) -> Result<(), Error> {
+        let bits = smp.bits() as u32;
+        let channels = decode(smp, &pcm)?;
+        let frames = channels[0].len();
+
+        writer.write_all(b"fLaC")?;
+        writer.write_all(&stream_info(smp.rate, channels.len(), bits, frames))?;
+
+        for (number, start) in (0..frames).step_by(BLOCK_SIZE).enumerate() {
+            let end = (start + BLOCK_SIZE).min(frames);
+            let block: Vec<&[i32]> = channels.iter().map(|ch| &ch[start..end]).collect();
+
+            writer.write_all(&frame(number as u64, bits, &block))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Convert the pcm to signed samples, one buffer per channel.
+///
+/// Stereo samples store the left channel first, followed by the right channel.
+fn decode(smp: &Sample, pcm: &[u8]) -> Result<Vec<Vec<i32>>, Error> {
+    let signed = smp.is_signed();
+
+    let samples: Vec<i32> = match smp.bits() {
+        8 => pcm
+            .iter()
+            .map(|&byte| match signed {
+                true => byte as i8 as i32,
+                false => byte as i32 - 128,
+            })
+            .collect(),
+        16 => pcm
+            .chunks_exact(2)
+            .map(|bytes| {
+                let value = u16::from_le_bytes([bytes[0], bytes[1]]);
+                match signed {
+                    true => value as i16 as i32,
+                    false => value as i32 - 32768,
+                }
+            })
+            .collect(),
+        bits => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{bits}-bit samples can't be exported to FLAC"),
+            )
+            .into())
+        }
+    };
+
+    Ok(match smp.is_stereo() {
+        true => {
+            let half = samples.len() / 2;
+            vec![samples[..half].to_vec(), samples[half..half * 2].to_vec()]
+        }
+        false => vec![samples],
+    })
+}
+
+/// The only metadata block. The frame sizes and MD5 signature are left unknown.
+fn stream_info(rate: u32, channels: usize, bits: u32, frames: usize) -> Vec<u8> {
+    let mut w = BitWriter::default();
+
+    w.write(1, 1); // last metadata block
+    w.write(7, 0); // STREAMINFO
+    w.write(24, 34);
+    w.write(16, BLOCK_SIZE as u64);
+    w.write(16, BLOCK_SIZE as u64);
+    w.write(24, 0);
+    w.write(24, 0);
+    w.write(20, rate.clamp(1, MAX_RATE) as u64);
+    w.write(3, channels as u64 - 1);
+    w.write(5, bits as u64 - 1);
+    w.write(36, frames as u64);
+
+    for _ in 0..4 {
+        w.write(32, 0);
+    }
+
+    w.bytes
+}
+
+fn frame(number: u64, bits: u32, channels: &[&[i32]]) -> Vec<u8> {
+    let mut w = BitWriter::default();
+    let len = channels[0].len();
+
+    w.write(14, 0b11_1111_1111_1110); // sync code
+    w.write(1, 0);
+    w.write(1, 0); // fixed block size
+    w.write(4, 0b0111); // block size is stored at the end of the header
+    w.write(4, 0b0000); // sample rate is stored in STREAMINFO
+    w.write(4, channels.len() as u64 - 1); // independent channels
+    w.write(3, if bits == 8 { 0b001 } else { 0b100 });
+    w.write(1, 0);
+    w.write_utf8(number);
+    w.write(16, len as u64 - 1);
+
+    let crc = crc8(&w.bytes);
+    w.write(8, crc as u64);
+
+    for samples in channels {
+        subframe(&mut w, bits, samples);
+    }
+
+    w.align();
+
+    let crc = crc16(&w.bytes);
+    w.write(16, crc as u64);
+
+    w.bytes
+}
+
+fn subframe(w: &mut BitWriter, bits: u32, samples: &[i32]) {
+    if samples.iter().all(|&sample| sample == samples[0]) {
+        w.write(8, 0b0000_0000); // CONSTANT
+        w.write(bits, samples[0] as u64);
+        return;
+    }
+
+    let verbatim = samples.len() as u64 * bits as u64;
+
+    let best = (0..=MAX_ORDER.min(samples.len() - 1))
+        .map(|order| {
+            let residual = residual(samples, order);
+            let param = rice_param(&residual);
+            let size = (order as u64 * bits as u64) + 10 + rice_size(&residual, param);
+
+            (order, residual, param, size)
+        })
+        .min_by_key(|(.., size)| *size);
+
+    match best {
+        Some((order, residual, param, size)) if size < verbatim => {
+            w.write(8, 0b0001_0000 | (order as u64) << 1); // FIXED
+            for &sample in &samples[..order] {
+                w.write(bits, sample as u64);
+            }
+
+            w.write(2, 0); // 4 bit Rice parameters
+            w.write(4, 0); // a single partition
+            w.write(4, param as u64);
+            for &value in &residual {
+                w.write_rice(param, zigzag(value));
+            }
+        }
+        _ => {
+            w.write(8, 0b0000_0010); // VERBATIM
+            for &sample in samples {
+                w.write(bits, sample as u64);
+            }
+        }
+    }
+}
+
+/// The residual of a fixed predictor is the nth order difference of the signal.
+fn residual(samples: &[i32], order: usize) -> Vec<i32> {
+    let mut residual = samples.to_vec();
+
+    for _ in 0..order {
+        residual = residual.windows(2).map(|w| w[1] - w[0]).collect();
+    }
+
+    residual
+}
+
+fn zigzag(value: i32) -> u32 {
+    ((value << 1) ^ (value >> 31)) as u32
+}
+
+fn rice_size(residual: &[i32], param: u32) -> u64 {
+    residual
+        .iter()
+        .map(|&value| (zigzag(value) >> param) as u64 + 1 + param as u64)
+        .sum()
+}
+
+fn rice_param(residual: &[i32]) -> u32 {
+    (0..=MAX_RICE_PARAM)
+        .min_by_key(|&param| rice_size(residual, param))
+        .unwrap_or_default()
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    len: u32,
+}
+
+impl BitWriter {
+    /// Write the lowest ``bits`` of ``value``, most significant bit first.
+    fn write(&mut self, bits: u32, value: u64) {
+        if bits > 32 {
+            self.write(bits - 32, value >> 32);
+            self.write(32, value);
+            return;
+        }
+
+        self.acc = (self.acc << bits) | (value & ((1 << bits) - 1));
+        self.len += bits;
+
+        while self.len >= 8 {
+            self.len -= 8;
+            self.bytes.push((self.acc >> self.len) as u8);
+        }
+    }
+
+    fn write_rice(&mut self, param: u32, value: u32) {
+        let mut quotient = value >> param;
+
+        while quotient >= 32 {
+            self.write(32, 0);
+            quotient -= 32;
+        }
+
+        self.write(quotient + 1, 1);
+        self.write(param, value as u64);
+    }
+
+    /// Frame numbers are stored like UTF-8 code points
+    fn write_utf8(&mut self, value: u64) {
+        if value < 0x80 {
+            return self.write(8, value);
+        }
+
+        let mut len = 2;
+        while value >= 1 << (5 * len + 1) {
+            len += 1;
+        }
+
+        let lead = (0xFF00 >> len) & 0xFF;
+        self.write(8, lead | value >> (6 * (len - 1)));
+
+        for i in (0..len - 1).rev() {
+            self.write(8, 0x80 | ((value >> (6 * i)) & 0x3F));
+        }
+    }
+
+    fn align(&mut self) {
+        if self.len > 0 {
+            self.write(8 - self.len, 0);
+        }
+    }
+}
+
+fn crc8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0, |crc, &byte| {
+        (0..8).fold(crc ^ byte, |crc, _| match crc & 0x80 {
+            0 => crc << 1,
+            _ => (crc << 1) ^ 0x07,
+        })
+    })
+}
+
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0, |crc, &byte| {
+        (0..8).fold(crc ^ ((byte as u16) << 8), |crc, _| match crc & 0x8000 {
+            0 => crc << 1,
+            _ => (crc << 1) ^ 0x8005,
+        })
+    })
+}
@@ -0,0 +1,187 @@
+//! Store loop points and the root note in exported WAV and AIFF files,
+//! so that samplers loop them the same way as the tracker did.
+//!
+//! The files are written by ``xmodits_lib`` first, then the extra chunks are appended.
+//!
+//! The sample rate of an exported sample is its C-5 speed,
+//! so the root note is always C-5 (MIDI note 60).
+
+use std::borrow::Cow;
+use std::io::Write;
+
+use xmodits_lib::interface::sample::LoopType;
+use xmodits_lib::{AudioTrait, Error, Sample};
+
+use super::chunk::Container;
+
+/// MIDI note of C-5
+pub(super) const ROOT_NOTE: u8 = 60;
+
+pub struct Looped {
+    inner: Box<dyn AudioTrait>,
+    container: Container,
+}
+
+impl Looped {
+    pub fn wav(inner: Box<dyn AudioTrait>) -> Self {
+        Self {
+            inner,
+            container: Container::Wav,
+        }
+    }
+
+    pub fn aiff(inner: Box<dyn AudioTrait>) -> Self {
+        Self {
+            inner,
+            container: Container::Aiff,
+        }
+    }
+}
+
+impl AudioTrait for Looped {
+    fn extension(&self) -> &str {
+        self.inner.extension()
+    }
+
+    fn write(&self, smp: &Sample, pcm: Cow<[u8]>, writer: &mut dyn Write) -> Result<(), Error> {
+        let mut file = Vec::new();
+        self.inner.write(smp, pcm, &mut file)?;
+
+        let looping = Loop::new(smp);
+
+        match self.container {
+            Container::Wav => {
+                self.container
+                    .append(&mut file, b"smpl", &smpl(smp.rate, looping))?
+            }
+            Container::Aiff => {
+                if let Some(looping) = looping {
+                    self.container.append(&mut file, b"MARK", &mark(looping))?;
+                }
+                self.container.append(&mut file, b"INST", &inst(looping))?;
+            }
+        }
+
+        writer.write_all(&file)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Loop {
+    /// First frame of the loop
+    pub start: u32,
+    /// Frame after the end of the loop
+    pub end: u32,
+    pub kind: LoopType,
+}
+
+impl Loop {
+    pub fn new(smp: &Sample) -> Option<Self> {
+        let start = smp.looping.start();
+        let end = smp.looping.end().min(smp.length_frames() as u32);
+
+        match smp.looping.kind() {
+            LoopType::Off => None,
+            _ if start >= end => None,
+            kind => Some(Self { start, end, kind }),
+        }
+    }
+}
+
+/// RIFF sampler chunk
+fn smpl(rate: u32, looping: Option<Loop>) -> Vec<u8> {
+    let loops = looping.iter().count() as u32;
+    let mut chunk = Vec::with_capacity(36 + 24 * loops as usize);
+
+    let mut put = |value: u32| chunk.extend(value.to_le_bytes());
+
+    put(0); // manufacturer
+    put(0); // product
+    put(1_000_000_000 / rate.max(1)); // sample period in nanoseconds
+    put(ROOT_NOTE as u32);
+    put(0); // pitch fraction
+    put(0); // SMPTE format
+    put(0); // SMPTE offset
+    put(loops);
+    put(0); // sampler data
+
+    if let Some(looping) = looping {
+        put(0); // cue point id
+        put(match looping.kind {
+            LoopType::PingPong => 1,
+            LoopType::Backward => 2,
+            _ => 0,
+        });
+        put(looping.start);
+        put(looping.end - 1); // the end is inclusive
+        put(0); // fraction
+        put(0); // play forever
+    }
+
+    chunk
+}
+
+const BEGIN_MARKER: u16 = 1;
+const END_MARKER: u16 = 2;
+
+/// AIFF markers for the start and end of a loop
+fn mark(looping: Loop) -> Vec<u8> {
+    let mut chunk = Vec::new();
+
+    chunk.extend(2u16.to_be_bytes());
+
+    for (id, position, name) in [
+        (BEGIN_MARKER, looping.start, "beg loop"),
+        (END_MARKER, looping.end, "end loop"),
+    ] {
+        chunk.extend(id.to_be_bytes());
+        chunk.extend(position.to_be_bytes());
+
+        // Pascal string, padded to an even length
+        chunk.push(name.len() as u8);
+        chunk.extend(name.as_bytes());
+        if name.len() % 2 == 0 {
+            chunk.push(0);
+        }
+    }
+
+    chunk
+}
+
+/// AIFF instrument chunk.
+///
+/// AIFF can't loop backwards, so backward loops are played forwards.
+fn inst(looping: Option<Loop>) -> Vec<u8> {
+    let (play_mode, begin, end): (i16, u16, u16) = match looping {
+        None => (0, 0, 0),
+        Some(Loop {
+            kind: LoopType::PingPong,
+            ..
+        }) => (2, BEGIN_MARKER, END_MARKER),
+        Some(_) => (1, BEGIN_MARKER, END_MARKER),
+    };
+
+    let mut chunk = Vec::with_capacity(20);
+    chunk.extend([
+        ROOT_NOTE, // base note
+        0,         // detune
+        0,         // low note
+        127,       // high note
+        1,         // low velocity
+        127,       // high velocity
+    ]);
+    chunk.extend(0i16.to_be_bytes()); // gain
+
+    // Sustain loop
+    chunk.extend(play_mode.to_be_bytes());
+    chunk.extend(begin.to_be_bytes());
+    chunk.extend(end.to_be_bytes());
+
+    // Release loop
+    chunk.extend(0i16.to_be_bytes());
+    chunk.extend(0u16.to_be_bytes());
+    chunk.extend(0u16.to_be_bytes());
+
+    chunk
+}
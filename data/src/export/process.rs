@@ -0,0 +1,325 @@
+//! Run the processing chain on a sample before it's exported.
+//!
+//! The steps run in this order: DC offset removal, silence trimming, channel conversion,
+//! resampling, normalization, fades, then bit depth conversion.
+//!
+//! The processed sample keeps its signedness.
+//! Loop points are moved along with trimmed and resampled audio.
+
+use std::borrow::Cow;
+
+use xmodits_lib::export::dsp;
+use xmodits_lib::interface::sample::{Channel, Depth, Loop as LoopPoint};
+use xmodits_lib::Sample;
+
+use super::loops::Loop;
+use super::resample;
+use crate::config::processing::{Channels, Normalize};
+use crate::config::ProcessingConfig;
+
+type Buffer = Vec<Vec<f32>>;
+
+/// Returns the processed sample, along with its new pcm data.
+pub fn apply(cfg: &ProcessingConfig, smp: &Sample, pcm: Cow<[u8]>) -> (Sample, Vec<u8>) {
+    let mut buffer = dsp::SampleBuffer::from(dsp::RawSample::new(smp, pcm)).buf;
+    let mut looping = Loop::new(smp);
+
+    if cfg.remove_dc_offset {
+        remove_dc_offset(&mut buffer);
+    }
+
+    if cfg.trim_silence {
+        trim_silence(&mut buffer, &mut looping, gain(cfg.silence_threshold));
+    }
+
+    buffer = convert_channels(buffer, cfg.channels);
+
+    let rate = match cfg.sample_rate {
+        0 => smp.rate,
+        rate => rate,
+    };
+
+    if rate != smp.rate {
+        buffer = buffer
+            .iter()
+            .map(|channel| resample::resample(channel, smp.rate, rate))
+            .collect();
+
+        if let Some(looping) = &mut looping {
+            let scale = |frame: u32| resample::frames(frame as usize, smp.rate, rate) as u32;
+            looping.start = scale(looping.start);
+            looping.end = scale(looping.end).min(frames(&buffer) as u32);
+        }
+    }
+
+    if cfg.normalize != Normalize::Off {
+        normalize(&mut buffer, cfg.normalize, gain(cfg.normalize_level));
+    }
+
+    let fade = (rate as u64 * cfg.fade_length as u64 / 1000) as usize;
+
+    if cfg.fade_in {
+        // Don't change the loop
+        let limit = looping.map_or(usize::MAX, |looping| looping.start as usize);
+        fade_in(&mut buffer, fade.min(limit));
+    }
+
+    if cfg.fade_out && looping.is_none() {
+        fade_out(&mut buffer, fade);
+    }
+
+    let bits = cfg.bit_depth.bits().unwrap_or(smp.bits());
+    let dither = cfg.dither && bits < smp.bits();
+
+    rebuild(smp, &buffer, rate, bits, dither, looping)
+}
+
+/// Convert a sample that was already decoded (e.g. by the sample player) back to pcm.
+///
+/// The sample keeps its bit depth, but stereo samples are stored with the left channel first.
+pub fn encode_buffer(smp: &Sample, buffer: &[Vec<f32>]) -> (Sample, Vec<u8>) {
+    rebuild(smp, buffer, smp.rate, smp.bits(), false, Loop::new(smp))
+}
+
+/// Encode the buffer, and update the sample to match it
+fn rebuild(
+    smp: &Sample,
+    buffer: &[Vec<f32>],
+    rate: u32,
+    bits: u8,
+    dither: bool,
+    looping: Option<Loop>,
+) -> (Sample, Vec<u8>) {
+    let pcm = encode(buffer, bits, smp.is_signed(), dither);
+
+    let mut processed = smp.clone();
+    processed.rate = rate;
+    processed.length = pcm.len() as u32;
+    processed.depth = match (bits, smp.is_signed()) {
+        (8, true) => Depth::I8,
+        (8, false) => Depth::U8,
+        (_, true) => Depth::I16,
+        (_, false) => Depth::U16,
+    };
+    processed.channel = match buffer.len() {
+        2 => Channel::Stereo { interleaved: false },
+        _ => Channel::Mono,
+    };
+
+    if let Some(looping) = looping {
+        processed.looping = LoopPoint::new(looping.start, looping.end, looping.kind);
+    }
+
+    (processed, pcm)
+}
+
+/// Convert decibels to amplitude
+fn gain(db: i8) -> f32 {
+    10f32.powf(db as f32 / 20.0)
+}
+
+fn frames(buffer: &[Vec<f32>]) -> usize {
+    buffer.first().map(Vec::len).unwrap_or_default()
+}
+
+fn remove_dc_offset(buffer: &mut Buffer) {
+    for channel in buffer.iter_mut().filter(|channel| !channel.is_empty()) {
+        let offset = channel.iter().sum::<f32>() / channel.len() as f32;
+        channel.iter_mut().for_each(|sample| *sample -= offset);
+    }
+}
+
+/// Samples that are completely silent are left alone.
+fn trim_silence(buffer: &mut Buffer, looping: &mut Option<Loop>, threshold: f32) {
+    let is_loud = |frame: usize| buffer.iter().any(|ch| ch[frame].abs() > threshold);
+
+    let Some(mut start) = (0..frames(buffer)).find(|&frame| is_loud(frame)) else {
+        return;
+    };
+    let mut end = (start..frames(buffer))
+        .rfind(|&frame| is_loud(frame))
+        .map_or(start, |end| end + 1);
+
+    if let Some(looping) = looping {
+        start = start.min(looping.start as usize);
+        end = end.max(looping.end as usize);
+
+        looping.start -= start as u32;
+        looping.end -= start as u32;
+    }
+
+    for channel in buffer.iter_mut() {
+        channel.truncate(end);
+        channel.drain(..start);
+    }
+}
+
+fn convert_channels(mut buffer: Buffer, channels: Channels) -> Buffer {
+    match (channels, buffer.len()) {
+        (Channels::Mono, 2) => {
+            let right = buffer.pop().unwrap_or_default();
+            let mut left = buffer.pop().unwrap_or_default();
+
+            left.iter_mut()
+                .zip(right)
+                .for_each(|(left, right)| *left = (*left + right) / 2.0);
+
+            vec![left]
+        }
+        (Channels::Stereo, 1) => vec![buffer[0].clone(), buffer[0].clone()],
+        _ => buffer,
+    }
+}
+
+fn normalize(buffer: &mut Buffer, mode: Normalize, level: f32) {
+    let samples = || buffer.iter().flatten();
+
+    let peak = samples().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+
+    if peak == 0.0 {
+        return;
+    }
+
+    let gain = match mode {
+        Normalize::Off => return,
+        Normalize::Peak => level / peak,
+        Normalize::Rms => {
+            let count = samples().count() as f32;
+            let rms = (samples().map(|sample| sample * sample).sum::<f32>() / count).sqrt();
+
+            // Don't clip
+            (level / rms).min(1.0 / peak)
+        }
+    };
+
+    buffer
+        .iter_mut()
+        .flatten()
+        .for_each(|sample| *sample *= gain);
+}
+
+fn fade_in(buffer: &mut Buffer, length: usize) {
+    let length = length.min(frames(buffer));
+
+    for channel in buffer.iter_mut() {
+        for (frame, sample) in channel[..length].iter_mut().enumerate() {
+            *sample *= frame as f32 / length as f32;
+        }
+    }
+}
+
+fn fade_out(buffer: &mut Buffer, length: usize) {
+    let frames = frames(buffer);
+    let length = length.min(frames);
+
+    for channel in buffer.iter_mut() {
+        for (frame, sample) in channel[frames - length..].iter_mut().enumerate() {
+            *sample *= 1.0 - (frame + 1) as f32 / length as f32;
+        }
+    }
+}
+
+/// Convert the buffer back to pcm. Stereo samples store the left channel first.
+///
+/// Samples are scaled the same way they're decoded, so decoding and encoding
+/// a sample without changing it gives the same pcm data.
+///
+/// If ``dither`` is set, triangular noise of one step is added before rounding.
+fn encode(buffer: &[Vec<f32>], bits: u8, signed: bool, dither: bool) -> Vec<u8> {
+    let mut pcm = Vec::with_capacity(buffer.len() * frames(buffer) * (bits as usize / 8));
+    let mut noise = Noise::default();
+
+    let scale = match bits {
+        8 => 128.0,
+        _ => 32768.0,
+    };
+
+    for sample in buffer.iter().flatten() {
+        let value = match dither {
+            true => sample * scale + noise.triangular(),
+            false => sample * scale,
+        };
+        let value = value.round().clamp(-scale, scale - 1.0);
+
+        match bits {
+            8 => {
+                let value = value as i8;
+                pcm.push(match signed {
+                    true => value as u8,
+                    false => (value as i16 + 128) as u8,
+                });
+            }
+            _ => {
+                let value = value as i16;
+                let bytes = match signed {
+                    true => value.to_le_bytes(),
+                    false => ((value as i32 + 32768) as u16).to_le_bytes(),
+                };
+                pcm.extend(bytes);
+            }
+        }
+    }
+
+    pcm
+}
+
+/// A small pseudo random number generator for dither.
+///
+/// It always starts with the same seed, so the same sample is always processed the same way.
+struct Noise(u32);
+
+impl Default for Noise {
+    fn default() -> Self {
+        Self(0x9E37_79B9)
+    }
+}
+
+impl Noise {
+    /// Xorshift, returns a value between 0 and 1
+    fn uniform(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        (self.0 >> 8) as f32 / (1 << 24) as f32
+    }
+
+    /// Returns a value between -1 and 1, most likely close to 0
+    fn triangular(&mut self) -> f32 {
+        self.uniform() - self.uniform()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decode every possible value, then check that encoding gives the same bytes.
+    fn round_trip(depth: Depth, pcm: &[u8]) {
+        let smp = Sample {
+            depth,
+            channel: Channel::Mono,
+            length: pcm.len() as u32,
+            ..Default::default()
+        };
+
+        let buffer = dsp::SampleBuffer::from(dsp::RawSample::new(&smp, Cow::Borrowed(pcm))).buf;
+
+        assert_eq!(encode(&buffer, smp.bits(), smp.is_signed(), false), pcm);
+    }
+
+    #[test]
+    fn round_trip_8_bit() {
+        let pcm: Vec<u8> = (0..=u8::MAX).collect();
+
+        round_trip(Depth::I8, &pcm);
+        round_trip(Depth::U8, &pcm);
+    }
+
+    #[test]
+    fn round_trip_16_bit() {
+        let pcm: Vec<u8> = (0..=u16::MAX).flat_map(u16::to_le_bytes).collect();
+
+        round_trip(Depth::I16, &pcm);
+        round_trip(Depth::U16, &pcm);
+    }
+}
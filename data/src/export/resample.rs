@@ -0,0 +1,66 @@
+//! Band-limited resampling with a windowed sinc filter.
+//!
+//! When the rate is lowered, the cutoff is moved down to the new Nyquist frequency,
+//! so that nothing above it folds back as aliasing.
+
+use std::f64::consts::PI;
+
+/// Zero crossings on each side of the filter, at the lowest cutoff
+const ZERO_CROSSINGS: f64 = 16.0;
+
+/// Resample a channel from ``from`` Hz to ``to`` Hz.
+pub fn resample(channel: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || from == 0 || to == 0 || channel.is_empty() {
+        return channel.to_vec();
+    }
+
+    let ratio = to as f64 / from as f64;
+    let length = frames(channel.len(), from, to);
+
+    // Relative to the Nyquist frequency of the input
+    let cutoff = ratio.min(1.0);
+    let width = ZERO_CROSSINGS / cutoff;
+
+    (0..length)
+        .map(|frame| {
+            let position = frame as f64 / ratio;
+
+            let first = (position - width).ceil().max(0.0) as usize;
+            let last = ((position + width).floor() as usize).min(channel.len() - 1);
+
+            let sum: f64 = (first..=last)
+                .map(|index| {
+                    let x = index as f64 - position;
+                    channel[index] as f64 * cutoff * sinc(cutoff * x) * blackman(x / width)
+                })
+                .sum();
+
+            sum as f32
+        })
+        .collect()
+}
+
+/// How many frames there will be after resampling
+pub fn frames(frames: usize, from: u32, to: u32) -> usize {
+    match from {
+        0 => frames,
+        from => ((frames as u64 * to as u64 + from as u64 / 2) / from as u64) as usize,
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    match x == 0.0 {
+        true => 1.0,
+        false => (PI * x).sin() / (PI * x),
+    }
+}
+
+/// ``x`` ranges from -1 to 1
+fn blackman(x: f64) -> f64 {
+    if x.abs() >= 1.0 {
+        return 0.0;
+    }
+
+    let phase = PI * (x + 1.0);
+    0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos()
+}
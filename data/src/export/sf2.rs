@@ -0,0 +1,299 @@
+//! SoundFont 2 banks with one preset per sample.
+//!
+//! Each preset plays a single instrument that covers the whole keyboard.
+//! Samples keep their sample rate and loop points, and play at their original pitch on C-5.
+//! Stereo samples are stored as a linked pair of left and right samples.
+
+use std::borrow::Cow;
+use std::io;
+use std::path::Path;
+
+use xmodits_lib::export::dsp;
+use xmodits_lib::Sample;
+
+use super::loops::{Loop, ROOT_NOTE};
+
+/// Names are 20 bytes, including the null terminator
+const NAME_SIZE: usize = 20;
+
+/// Size of a record in ``shdr``
+const SAMPLE_HEADER_SIZE: usize = 46;
+
+/// Every sample is followed by this many zeroes
+const PADDING: usize = 46;
+
+/// Generators used by the instrument zones
+const PAN: u16 = 17;
+const INSTRUMENT: u16 = 41;
+const SAMPLE_ID: u16 = 53;
+const SAMPLE_MODES: u16 = 54;
+
+/// Sample types
+const MONO: u16 = 1;
+const RIGHT: u16 = 2;
+const LEFT: u16 = 4;
+
+struct Entry {
+    name: String,
+    rate: u32,
+    looping: Option<Loop>,
+    channels: Vec<Vec<i16>>,
+}
+
+pub struct SoundFont {
+    name: String,
+    entries: Vec<Entry>,
+}
+
+impl SoundFont {
+    /// ``name`` is usually the title of the module
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.trim().to_owned(),
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, smp: &Sample, pcm: Cow<[u8]>) {
+        let buffer = dsp::SampleBuffer::from(dsp::RawSample::new(smp, pcm)).buf;
+
+        let channels = buffer
+            .iter()
+            .map(|channel| {
+                channel
+                    .iter()
+                    .map(|&sample| (sample * 32768.0).round().clamp(-32768.0, 32767.0) as i16)
+                    .collect()
+            })
+            .collect();
+
+        let name = match smp.name.trim() {
+            "" => format!("Sample {}", smp.index_raw()),
+            name => name.to_owned(),
+        };
+
+        self.entries.push(Entry {
+            name,
+            rate: smp.rate.max(1),
+            looping: Loop::new(smp),
+            channels,
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        std::fs::write(path, self.render())
+    }
+
+    fn render(&self) -> Vec<u8> {
+        let mut smpl = Vec::new();
+        let mut hydra = Hydra::default();
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            let stereo = entry.channels.len() == 2;
+            let first_header = hydra.shdr.len() / SAMPLE_HEADER_SIZE;
+
+            // Preset
+            name(&mut hydra.phdr, &entry.name);
+            put16(&mut hydra.phdr, (index % 128) as u16);
+            put16(&mut hydra.phdr, (index / 128) as u16);
+            put16(&mut hydra.phdr, (hydra.pbag.len() / 4) as u16);
+            hydra.phdr.extend([0; 12]);
+
+            put16(&mut hydra.pbag, (hydra.pgen.len() / 4) as u16);
+            put16(&mut hydra.pbag, 0);
+            generator(&mut hydra.pgen, INSTRUMENT, index as u16);
+
+            // Instrument
+            name(&mut hydra.inst, &entry.name);
+            put16(&mut hydra.inst, (hydra.ibag.len() / 4) as u16);
+
+            for (channel, data) in entry.channels.iter().enumerate() {
+                let header = first_header + channel;
+                let start = (smpl.len() / 2) as u32;
+
+                for sample in data.iter().chain([0; PADDING].iter()) {
+                    smpl.extend(sample.to_le_bytes());
+                }
+
+                put16(&mut hydra.ibag, (hydra.igen.len() / 4) as u16);
+                put16(&mut hydra.ibag, 0);
+
+                if stereo {
+                    let pan: i16 = if channel == 0 { -500 } else { 500 };
+                    generator(&mut hydra.igen, PAN, pan as u16);
+                }
+                if entry.looping.is_some() {
+                    generator(&mut hydra.igen, SAMPLE_MODES, 1);
+                }
+                generator(&mut hydra.igen, SAMPLE_ID, header as u16);
+
+                // Sample header
+                let (loop_start, loop_end) = match entry.looping {
+                    Some(looping) => (looping.start, looping.end),
+                    None => (0, 0),
+                };
+
+                let (link, kind) = match (stereo, channel) {
+                    (false, _) => (0, MONO),
+                    (true, 0) => (header + 1, LEFT),
+                    (true, _) => (header - 1, RIGHT),
+                };
+
+                name(&mut hydra.shdr, &entry.name);
+                put32(&mut hydra.shdr, start);
+                put32(&mut hydra.shdr, start + data.len() as u32);
+                put32(&mut hydra.shdr, start + loop_start);
+                put32(&mut hydra.shdr, start + loop_end);
+                put32(&mut hydra.shdr, entry.rate);
+                hydra.shdr.extend([ROOT_NOTE, 0]);
+                put16(&mut hydra.shdr, link as u16);
+                put16(&mut hydra.shdr, kind);
+            }
+        }
+
+        hydra.terminate();
+
+        let title = match self.name.as_str() {
+            "" => "Untitled",
+            name => name,
+        };
+
+        let info = list(
+            b"INFO",
+            &[
+                chunk(b"ifil", &[2, 0, 1, 0]),
+                chunk(b"isng", &zstr("EMU8000")),
+                chunk(b"INAM", &zstr(title)),
+                chunk(b"ISFT", &zstr("XMODITS")),
+            ],
+        );
+
+        let sdta = list(b"sdta", &[chunk(b"smpl", &smpl)]);
+
+        let pdta = list(
+            b"pdta",
+            &[
+                chunk(b"phdr", &hydra.phdr),
+                chunk(b"pbag", &hydra.pbag),
+                chunk(b"pmod", &hydra.pmod),
+                chunk(b"pgen", &hydra.pgen),
+                chunk(b"inst", &hydra.inst),
+                chunk(b"ibag", &hydra.ibag),
+                chunk(b"imod", &hydra.imod),
+                chunk(b"igen", &hydra.igen),
+                chunk(b"shdr", &hydra.shdr),
+            ],
+        );
+
+        let mut sfbk = b"sfbk".to_vec();
+        sfbk.extend(info);
+        sfbk.extend(sdta);
+        sfbk.extend(pdta);
+
+        chunk(b"RIFF", &sfbk)
+    }
+}
+
+/// The preset, instrument and sample headers
+#[derive(Default)]
+struct Hydra {
+    phdr: Vec<u8>,
+    pbag: Vec<u8>,
+    pmod: Vec<u8>,
+    pgen: Vec<u8>,
+    inst: Vec<u8>,
+    ibag: Vec<u8>,
+    imod: Vec<u8>,
+    igen: Vec<u8>,
+    shdr: Vec<u8>,
+}
+
+impl Hydra {
+    /// Every list ends with a terminal record
+    fn terminate(&mut self) {
+        name(&mut self.phdr, "EOP");
+        self.phdr.extend([0; 4]);
+        put16(&mut self.phdr, (self.pbag.len() / 4) as u16);
+        self.phdr.extend([0; 12]);
+
+        put16(&mut self.pbag, (self.pgen.len() / 4) as u16);
+        put16(&mut self.pbag, 0);
+        self.pmod.extend([0; 10]);
+        self.pgen.extend([0; 4]);
+
+        name(&mut self.inst, "EOI");
+        put16(&mut self.inst, (self.ibag.len() / 4) as u16);
+
+        put16(&mut self.ibag, (self.igen.len() / 4) as u16);
+        put16(&mut self.ibag, 0);
+        self.imod.extend([0; 10]);
+        self.igen.extend([0; 4]);
+
+        name(&mut self.shdr, "EOS");
+        self.shdr.extend([0; 26]);
+    }
+}
+
+fn put16(buf: &mut Vec<u8>, value: u16) {
+    buf.extend(value.to_le_bytes());
+}
+
+fn put32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend(value.to_le_bytes());
+}
+
+fn generator(buf: &mut Vec<u8>, operator: u16, amount: u16) {
+    put16(buf, operator);
+    put16(buf, amount);
+}
+
+/// ASCII name, truncated and padded with zeroes to 20 bytes
+fn name(buf: &mut Vec<u8>, name: &str) {
+    let mut bytes: Vec<u8> = name
+        .chars()
+        .map(|c| match c.is_ascii() && !c.is_ascii_control() {
+            true => c as u8,
+            false => b'_',
+        })
+        .take(NAME_SIZE - 1)
+        .collect();
+
+    bytes.resize(NAME_SIZE, 0);
+    buf.extend(bytes);
+}
+
+/// Null terminated string, padded to an even length
+fn zstr(string: &str) -> Vec<u8> {
+    let mut bytes = string.as_bytes().to_vec();
+    bytes.push(0);
+
+    if bytes.len() % 2 == 1 {
+        bytes.push(0);
+    }
+
+    bytes
+}
+
+fn chunk(id: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut chunk = Vec::with_capacity(8 + data.len() + 1);
+    chunk.extend(id);
+    put32(&mut chunk, data.len() as u32);
+    chunk.extend(data);
+
+    if data.len() % 2 == 1 {
+        chunk.push(0);
+    }
+
+    chunk
+}
+
+fn list(kind: &[u8; 4], chunks: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = kind.to_vec();
+    chunks.iter().for_each(|chunk| data.extend(chunk));
+
+    chunk(b"LIST", &data)
+}
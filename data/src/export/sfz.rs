@@ -0,0 +1,118 @@
+//! SFZ instruments that play the samples ripped from a module.
+//!
+//! Each sample is mapped to its own key, starting at C2 (MIDI note 36),
+//! and plays at its original pitch on that key.
+//! Loop points are in frames, and ``loop_end`` is inclusive.
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Component, Path};
+
+use xmodits_lib::interface::sample::LoopType;
+use xmodits_lib::Sample;
+
+use super::loops::Loop;
+
+/// Key of the first sample
+const FIRST_KEY: usize = 36;
+
+/// MIDI only has 128 keys
+const KEYS: usize = 128;
+
+struct Region {
+    name: String,
+    /// Path of the sample, relative to the SFZ file if possible
+    sample: String,
+    looping: Option<Loop>,
+}
+
+#[derive(Default)]
+pub struct Instrument {
+    regions: Vec<Region>,
+}
+
+impl Instrument {
+    /// Add a sample that was exported to ``path``.
+    ///
+    /// ``folder`` is where the SFZ file will be saved.
+    pub fn add(&mut self, smp: &Sample, path: &Path, folder: &Path) {
+        let sample = match path.strip_prefix(folder) {
+            // Forward slashes work on every platform
+            Ok(relative) => relative
+                .components()
+                .filter_map(|component| match component {
+                    Component::Normal(part) => Some(part.to_string_lossy()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("/"),
+            Err(_) => path.display().to_string(),
+        };
+
+        self.regions.push(Region {
+            name: smp.name.trim().replace(char::is_control, " "),
+            sample,
+            looping: Loop::new(smp),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// Save the instrument. ``source`` is the module it was ripped from.
+    pub fn save(&self, path: &Path, source: &Path) -> io::Result<()> {
+        std::fs::write(path, self.render(source))
+    }
+
+    fn render(&self, source: &Path) -> String {
+        let mut sfz = String::new();
+        let first = FIRST_KEY.min(KEYS.saturating_sub(self.regions.len()));
+
+        let _ = writeln!(sfz, "// Ripped from \"{}\" by XMODITS", source.display());
+
+        if self.regions.len() > KEYS {
+            let _ = writeln!(
+                sfz,
+                "// Only the first {KEYS} of {} samples could be mapped",
+                self.regions.len()
+            );
+        }
+
+        for (key, region) in (first..KEYS).zip(&self.regions) {
+            let _ = writeln!(sfz);
+
+            if !region.name.is_empty() {
+                let _ = writeln!(sfz, "// {}", region.name);
+            }
+
+            let _ = write!(
+                sfz,
+                "<region> lokey={key} hikey={key} pitch_keycenter={key}"
+            );
+
+            match region.looping {
+                Some(looping) => {
+                    let _ = write!(
+                        sfz,
+                        " loop_mode=loop_continuous loop_start={} loop_end={}",
+                        looping.start,
+                        looping.end - 1
+                    );
+
+                    match looping.kind {
+                        LoopType::PingPong => sfz.push_str(" loop_type=alternate"),
+                        LoopType::Backward => sfz.push_str(" loop_type=backward"),
+                        _ => (),
+                    }
+                }
+                None => sfz.push_str(" loop_mode=no_loop"),
+            }
+
+            // The path has to be last, as it can contain spaces
+            let _ = writeln!(sfz, " sample={}", region.sample);
+        }
+
+        sfz
+    }
+}
@@ -0,0 +1,161 @@
+//! Embed where a sample came from into an exported file.
+//!
+//! WAV files get a ``LIST/INFO`` chunk, AIFF files get ``NAME`` and ``ANNO`` chunks,
+//! and FLAC files get Vorbis comments. The other formats can't store them.
+
+use std::io;
+use std::path::Path;
+
+use super::chunk::Container;
+use super::ExportFormat;
+
+const SOFTWARE: &str = "XMODITS";
+
+/// Where a sample came from
+pub struct Tags<'a> {
+    pub name: &'a str,
+    pub filename: Option<&'a str>,
+    /// Title of the module
+    pub module: &'a str,
+    pub source: &'a Path,
+    /// Tracker format of the module
+    pub format: &'a str,
+    /// Internal index of the sample
+    pub index: usize,
+}
+
+impl Tags<'_> {
+    /// Add the tags to a file that was just exported.
+    pub fn embed(&self, format: ExportFormat, file: &mut Vec<u8>) -> io::Result<()> {
+        match format {
+            ExportFormat::Wav => self.wav(file),
+            ExportFormat::Aiff => self.aiff(file),
+            ExportFormat::Flac => self.flac(file),
+            _ => Ok(()),
+        }
+    }
+
+    /// Every field as ``(label, value)``, empty ones are left out.
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        [
+            ("Name", self.name.to_owned()),
+            ("Module", self.module.to_owned()),
+            ("Filename", self.filename.unwrap_or_default().to_owned()),
+            ("Source", self.source.display().to_string()),
+            ("Format", self.format.to_owned()),
+            ("Index", self.index.to_string()),
+        ]
+        .into_iter()
+        .map(|(label, value)| (label, value.trim().to_owned()))
+        .filter(|(_, value)| !value.is_empty())
+        .collect()
+    }
+
+    fn wav(&self, file: &mut Vec<u8>) -> io::Result<()> {
+        let mut list = b"INFO".to_vec();
+        let mut comment = Vec::new();
+
+        for (label, value) in self.fields() {
+            let id = match label {
+                "Name" => b"INAM",
+                "Module" => b"IPRD",
+                "Source" => b"ISRC",
+                _ => {
+                    comment.push(format!("{label}: {value}"));
+                    continue;
+                }
+            };
+            info(&mut list, id, &value);
+        }
+
+        if !comment.is_empty() {
+            info(&mut list, b"ICMT", &comment.join("\n"));
+        }
+        info(&mut list, b"ISFT", SOFTWARE);
+
+        Container::Wav.append(file, b"LIST", &list)
+    }
+
+    fn aiff(&self, file: &mut Vec<u8>) -> io::Result<()> {
+        for (label, value) in self.fields() {
+            match label {
+                "Name" => Container::Aiff.append(file, b"NAME", value.as_bytes())?,
+                _ => {
+                    let annotation = format!("{label}: {value}");
+                    Container::Aiff.append(file, b"ANNO", annotation.as_bytes())?
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Insert a ``VORBIS_COMMENT`` block after ``STREAMINFO``
+    fn flac(&self, file: &mut Vec<u8>) -> io::Result<()> {
+        const STREAMINFO_END: usize = 4 + 4 + 34;
+        const VORBIS_COMMENT: u8 = 4;
+
+        if file.len() < STREAMINFO_END || !file.starts_with(b"fLaC") {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Unexpected file header",
+            ));
+        }
+
+        let comments: Vec<String> = self
+            .fields()
+            .into_iter()
+            .map(|(label, value)| {
+                let key = match label {
+                    "Name" => "TITLE",
+                    "Module" => "ALBUM",
+                    "Filename" => "SAMPLE_FILENAME",
+                    "Source" => "SOURCE",
+                    "Format" => "TRACKER_FORMAT",
+                    _ => "SAMPLE_INDEX",
+                };
+                format!("{key}={value}")
+            })
+            .collect();
+
+        let mut block = Vec::new();
+
+        vorbis_string(&mut block, SOFTWARE);
+        block.extend((comments.len() as u32).to_le_bytes());
+        for comment in &comments {
+            vorbis_string(&mut block, comment);
+        }
+
+        // Take over the "last metadata block" flag from STREAMINFO
+        let last = file[4] & 0x80;
+        file[4] &= 0x7F;
+
+        let mut header = vec![last | VORBIS_COMMENT];
+        header.extend(&(block.len() as u32).to_be_bytes()[1..]);
+        header.extend(block);
+
+        file.splice(STREAMINFO_END..STREAMINFO_END, header);
+
+        Ok(())
+    }
+}
+
+/// RIFF ``INFO`` strings are null terminated, and padded to an even length
+fn info(list: &mut Vec<u8>, id: &[u8; 4], value: &str) {
+    let len = value.len() + 1;
+
+    list.extend(id);
+    list.extend((len as u32).to_le_bytes());
+    list.extend(value.as_bytes());
+    list.push(0);
+
+    if len % 2 == 1 {
+        list.push(0);
+    }
+}
+
+/// Vorbis comment strings are prefixed with their length
+fn vorbis_string(block: &mut Vec<u8>, value: &str) {
+    block.extend((value.len() as u32).to_le_bytes());
+    block.extend(value.as_bytes());
+}
@@ -0,0 +1,69 @@
+//! Lossy Ogg Vorbis encoder, only available with the ``vorbis`` feature.
+//!
+//! Without it, every sample fails to export instead.
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+#[cfg(feature = "vorbis")]
+use std::num::{NonZeroU32, NonZeroU8};
+
+#[cfg(feature = "vorbis")]
+use vorbis_rs::VorbisEncoderBuilder;
+#[cfg(feature = "vorbis")]
+use xmodits_lib::export::dsp;
+use xmodits_lib::{AudioTrait, Error, Sample};
+
+/// Whether XMODITS was built with the encoder
+pub const AVAILABLE: bool = cfg!(feature = "vorbis");
+
+/// Frames passed to the encoder at a time
+#[cfg(feature = "vorbis")]
+const CHUNK_SIZE: usize = 4096;
+
+pub struct Vorbis;
+
+impl AudioTrait for Vorbis {
+    fn extension(&self) -> &str {
+        "ogg"
+    }
+
+    #[cfg(not(feature = "vorbis"))]
+    fn write(&self, _: &Sample, _: Cow<[u8]>, _: &mut dyn Write) -> Result<(), Error> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, UNAVAILABLE).into())
+    }
+
+    #[cfg(feature = "vorbis")]
+    fn write(&self, smp: &Sample, pcm: Cow<[u8]>, writer: &mut dyn Write) -> Result<(), Error> {
+        let buffer = dsp::SampleBuffer::from(dsp::RawSample::new(smp, pcm)).buf;
+
+        let rate = NonZeroU32::new(smp.rate).ok_or_else(|| invalid("Sample rate is zero"))?;
+        let channels = NonZeroU8::new(buffer.len() as u8).ok_or_else(|| invalid("No channels"))?;
+
+        let mut encoder = VorbisEncoderBuilder::new(rate, channels, writer)
+            .and_then(|mut builder| builder.build())
+            .map_err(io::Error::other)?;
+
+        let frames = buffer.first().map(Vec::len).unwrap_or_default();
+
+        for start in (0..frames).step_by(CHUNK_SIZE) {
+            let end = (start + CHUNK_SIZE).min(frames);
+            let block: Vec<&[f32]> = buffer.iter().map(|ch| &ch[start..end]).collect();
+
+            encoder
+                .encode_audio_block(&block)
+                .map_err(io::Error::other)?;
+        }
+
+        encoder.finish().map_err(io::Error::other)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "vorbis"))]
+const UNAVAILABLE: &str = "XMODITS was built without Ogg Vorbis support";
+
+#[cfg(feature = "vorbis")]
+fn invalid(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
@@ -0,0 +1,176 @@
+//! A record of previous rips, stored in the config directory.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+
+use crate::config::{config_dir, FilterConfig, SampleNameConfig, SampleRippingConfig};
+use crate::Time;
+
+const HISTORY_DIR: &str = "history";
+const HISTORY_NAME: &str = "history.toml";
+
+/// Only keep the most recent jobs
+pub const MAX_RECORDS: usize = 50;
+
+/// The samples to rip from a module, by their internal index.
+///
+/// Modules that aren't listed have all of their samples ripped.
+pub type Selection = BTreeMap<PathBuf, BTreeSet<usize>>;
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+pub struct History {
+    /// Ordered from oldest to newest
+    #[serde(default)]
+    pub records: Vec<Record>,
+}
+
+/// A job that has finished
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Record {
+    pub date: DateTime<Local>,
+    pub destination: PathBuf,
+    pub entries: Vec<PathBuf>,
+    /// How long the rip took, in seconds
+    pub elapsed: f32,
+    pub outcome: Outcome,
+    /// Where the errors were saved
+    pub error_log: Option<PathBuf>,
+    pub ripping: SampleRippingConfig,
+    pub naming: SampleNameConfig,
+    pub filters: FilterConfig,
+    /// Only some of the samples were ripped from these modules
+    #[serde(default, skip_serializing_if = "Selection::is_empty")]
+    pub samples: Selection,
+}
+
+/// A summary of how a job finished
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    NoErrors,
+    Cancelled,
+    Aborted,
+    Errors { total: u64 },
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoErrors => write!(f, "Done"),
+            Self::Cancelled => write!(f, "Cancelled"),
+            Self::Aborted => write!(f, "Aborted"),
+            Self::Errors { total: 1 } => write!(f, "Done, 1 error"),
+            Self::Errors { total } => write!(f, "Done, {total} errors"),
+        }
+    }
+}
+
+impl Record {
+    /// Record a job that has just finished
+    pub fn new(
+        entries: Vec<PathBuf>,
+        ripping: SampleRippingConfig,
+        naming: SampleNameConfig,
+        filters: FilterConfig,
+        samples: Selection,
+        outcome: Outcome,
+        time: &Time,
+        error_log: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            date: Local::now(),
+            destination: ripping.destination.clone(),
+            entries,
+            elapsed: time.elapsed(),
+            outcome,
+            error_log,
+            ripping,
+            naming,
+            filters,
+            samples,
+        }
+    }
+
+    pub fn time(&self) -> Time {
+        Time::from_secs(self.elapsed)
+    }
+}
+
+impl History {
+    pub fn load() -> Self {
+        let Ok(toml) = fs::read_to_string(Self::path()) else {
+            return Self::default();
+        };
+
+        toml::from_str(&toml).unwrap_or_else(|e| {
+            warn!("Could not parse the rip history.");
+            error!("{}", e);
+            Self::default()
+        })
+    }
+
+    /// The history is written to a temporary file first,
+    /// so it's never left half written if XMODITS closes unexpectedly.
+    pub fn save(&self) -> anyhow::Result<()> {
+        fs::create_dir_all(Self::dir())?;
+
+        let path = Self::path();
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, toml::to_string_pretty(&self)?)?;
+        fs::rename(&temp, &path)?;
+
+        info!("Saved rip history");
+        Ok(())
+    }
+
+    /// Add a record, removing the oldest ones if there are too many.
+    pub fn push(&mut self, record: Record) {
+        self.records.push(record);
+
+        if self.records.len() > MAX_RECORDS {
+            let excess = self.records.len() - MAX_RECORDS;
+            self.records.drain(..excess).for_each(|r| remove_log(&r));
+        }
+    }
+
+    /// Remove a record, along with its error log if it was stored in the history folder.
+    pub fn remove(&mut self, index: usize) -> Option<Record> {
+        if index >= self.records.len() {
+            return None;
+        }
+
+        let record = self.records.remove(index);
+        remove_log(&record);
+        Some(record)
+    }
+
+    pub fn clear(&mut self) {
+        self.records.drain(..).for_each(|r| remove_log(&r));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Error logs that aren't placed in the destination are stored here.
+    pub fn dir() -> PathBuf {
+        config_dir().join(HISTORY_DIR)
+    }
+
+    pub fn path() -> PathBuf {
+        Self::dir().join(HISTORY_NAME)
+    }
+}
+
+/// Only logs owned by the history are removed.
+fn remove_log(record: &Record) {
+    if let Some(log) = &record.error_log {
+        if log.starts_with(History::dir()) {
+            let _ = fs::remove_file(log);
+        }
+    }
+}
@@ -1,23 +1,19 @@
 //! Data components of XMODITS
 
+pub mod analysis;
 pub mod config;
+pub mod export;
+pub mod history;
 pub mod theme;
 pub mod time;
 
 pub use config::Config;
+pub use export::ExportFormat;
+pub use history::History;
 pub use theme::Theme;
 pub use time::Time;
 
-use xmodits_lib::export::Format;
-
-pub const SUPPORTED_FORMATS: &[Format] = &[
-    Format::WAV,
-    Format::AIFF,
-    Format::ITS,
-    Format::S3I,
-    Format::IFF,
-    Format::RAW,
-];
+pub const SUPPORTED_FORMATS: &[ExportFormat] = ExportFormat::ALL;
 
 #[cfg(feature = "manual")]
 pub static MANUAL: &str = include_str!("../../assets/manual.txt");
\ No newline at end of file
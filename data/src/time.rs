@@ -31,6 +31,14 @@ impl Time {
     pub fn init() -> Self {
         Self::default()
     }
+
+    /// A stopped timer that took the given amount of seconds
+    pub fn from_secs(secs: f32) -> Self {
+        Self {
+            start: Instant::now(),
+            duration: Duration::from_secs_f32(secs.max(0.0)),
+        }
+    }
 }
 
 impl std::fmt::Display for Time {
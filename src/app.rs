@@ -6,11 +6,14 @@ use crate::font;
 use crate::icon;
 use crate::ripper;
 use crate::screen::about;
+use crate::screen::config::custom_filters::{self, CustomFilters};
 use crate::screen::config::name_preview;
+use crate::screen::config::processing;
 use crate::screen::config::sample_naming;
 use crate::screen::config::sample_ripping::{self, DESTINATION_BAR_ID};
 use crate::screen::crash::{self, Crashes};
 use crate::screen::entry::Entries;
+use crate::screen::history;
 use crate::screen::ripping;
 use crate::screen::sample_player;
 use crate::screen::settings;
@@ -21,8 +24,11 @@ use crate::utils::{files_dialog, folders_dialog};
 use crate::widget::helpers::{action, text_icon, warning};
 use crate::widget::{Container, Element};
 
-use data::Config;
+use data::config::sample_ripping::Collision;
+use data::config::FilterConfig;
+use data::{Config, History};
 pub use ripping::RippingState;
+use std::collections::BTreeSet;
 use std::path::PathBuf;
 use std::sync::OnceLock;
 
@@ -43,16 +49,25 @@ pub enum Message {
     DeleteSelected,
     Event(event::Event),
     FileDialog,
+    Filters(custom_filters::Message),
+    FiltersPressed,
     FolderDialog,
     FontLoaded(Result<(), iced::font::Error>),
     GeneralCfg(settings::Message),
+    History(history::Message),
+    HistoryPressed,
     Ignore,
     InvertSelection,
     NamingCfg(sample_naming::Message),
     Open(String),
+    Pause,
     PreviewSamples(PathBuf),
+    Resume,
+    ResumeJob,
     Probe(usize),
     ProbeResult(TrackerInfo),
+    ProcessingCfg(processing::Message),
+    ProcessingPressed,
     RippingCfg(sample_ripping::Message),
     SamplePlayer(sample_player::Message),
     SaveConfig,
@@ -74,7 +89,10 @@ pub enum Message {
 pub enum View {
     #[default]
     Configure,
+    Filters,
+    Processing,
     Settings,
+    History,
     About,
 }
 
@@ -92,6 +110,10 @@ pub struct XMODITS {
     naming_cfg: data::config::SampleNameConfig,
     ripping_cfg: data::config::SampleRippingConfig,
     general_cfg: data::config::GeneralConfig,
+    filters: CustomFilters,
+    /// There's a job that can be resumed
+    resumable: bool,
+    history: History,
     main_id: Option<window::Id>,
 }
 
@@ -131,19 +153,16 @@ impl XMODITS {
         self.ripping_cfg = config.ripping;
         self.naming_cfg = config.naming;
         self.general_cfg = config.general;
+        self.filters = CustomFilters::new(config.filters);
     }
 
-    pub fn build_start_signal(&mut self) -> ripper::Signal {
-        self.tracker_info.clear();
-        let entries = self.entries.take();
+    /// A signal that rips ``entries`` with the current configuration
+    pub fn build_signal(&self, entries: Vec<PathBuf>) -> ripper::Signal {
         let ripping = self.ripping_cfg.to_owned();
         let naming = self.naming_cfg.to_owned();
+        let filters = self.filters.config.to_owned();
 
-        ripper::Signal {
-            entries,
-            ripping,
-            naming,
-        }
+        ripper::Signal::new(entries, ripping, naming, filters)
     }
 
     pub fn clear_entries(&mut self) {
@@ -165,13 +184,16 @@ impl XMODITS {
         match &self.state {
             RippingState::Idle | RippingState::Finished { .. } => TITLE.to_string(),
             RippingState::Ripping {
-                message, progress, ..
+                message,
+                progress,
+                paused,
+                ..
             } => {
-                let info = format!(
-                    "{} - {}%",
-                    message.as_deref().unwrap_or("Ripping..."),
-                    progress.floor()
-                );
+                let message = match paused {
+                    true => "Paused",
+                    false => message.as_deref().unwrap_or("Ripping..."),
+                };
+                let info = format!("{} - {}%", message, progress.floor());
                 format!("{TITLE} - {info}")
             }
         }
@@ -181,38 +203,114 @@ impl XMODITS {
         let config = data::Config {
             general: self.general_cfg.clone(),
             ripping: self.ripping_cfg.clone(),
-            naming: self.naming_cfg,
+            naming: self.naming_cfg.clone(),
+            filters: self.filters.config.clone(),
         };
 
         Task::perform(async move { config.save().await }, |_| Message::Ignore)
     }
 
     pub fn start_ripping(&mut self) -> Task<Message> {
-        if self.state.is_ripping() | self.entries.is_empty() | !self.ripper.is_active() {
+        if self.entries.is_empty() {
             return Task::none();
         }
 
-        if !sample_ripping::destination_is_valid(&self.ripping_cfg) {
-            tracing::error!(
-                "The provided destination is not valid. The *parent* folder must exist."
-            );
-            return text_input::focus(DESTINATION_BAR_ID.clone());
+        // Check the configuration before the entries are taken, so they're kept if it's invalid
+        let mut start_signal = self.build_signal(Vec::new());
+
+        if let Err(task) = self.check(&start_signal) {
+            return task;
+        }
+
+        start_signal.entries = self.entries.take();
+        self.begin(start_signal)
+    }
+
+    /// Continue the last job that was cancelled or interrupted
+    pub fn resume_job(&mut self) -> Task<Message> {
+        if self.state.is_ripping() {
+            return Task::none();
+        }
+
+        let Some(job) = ripper::Job::load() else {
+            self.resumable = false;
+            return Task::none();
+        };
+
+        self.begin(ripper::Signal::from(job))
+    }
+
+    /// Rip the samples that were checked in the sample player
+    pub fn rip_selected(&mut self, module: PathBuf, samples: BTreeSet<usize>) -> Task<Message> {
+        if samples.is_empty() {
+            return Task::none();
+        }
+
+        // The module was picked explicitly, so the filters don't apply
+        let mut signal = self.build_signal(vec![module.clone()]);
+        signal.filters = FilterConfig::default();
+        signal.samples.insert(module, samples);
+
+        self.begin(signal)
+    }
+
+    /// Rip a previous job again, using the same entries and configuration
+    pub fn rip_again(&mut self, record: data::history::Record) -> Task<Message> {
+        self.begin(ripper::Signal::from(record))
+    }
+
+    /// Start ripping if every precondition is met.
+    fn begin(&mut self, signal: ripper::Signal) -> Task<Message> {
+        if signal.entries.is_empty() {
+            return Task::none();
         }
 
-        let start_signal = self.build_start_signal();
+        if let Err(task) = self.check(&signal) {
+            return task;
+        }
+
+        self.tracker_info.clear();
         self.ripper
-            .send(start_signal)
+            .send(signal)
             .expect("Sending start signal to Ripper.");
 
         self.state = RippingState::Ripping {
             message: None,
             progress: 0.0,
             errors: 0,
+            paused: false,
         };
 
         Task::none()
     }
 
+    /// Check everything a rip needs apart from its entries.
+    ///
+    /// If the configuration is invalid, the view where it can be fixed is shown instead.
+    fn check(&mut self, signal: &ripper::Signal) -> Result<(), Task<Message>> {
+        if self.state.is_ripping() | !self.ripper.is_active() {
+            return Err(Task::none());
+        }
+
+        if !sample_ripping::destination_is_valid(&signal.ripping) {
+            tracing::error!(
+                "The provided destination is not valid. The *parent* folder must exist."
+            );
+            return Err(text_input::focus(DESTINATION_BAR_ID.clone()));
+        }
+
+        if let Err(error) = signal.validate() {
+            tracing::error!("Can't start ripping. {error}");
+            self.view = match signal.filters.build() {
+                Err(_) => View::Filters,
+                Ok(_) => View::Configure,
+            };
+            return Err(Task::none());
+        }
+
+        Ok(())
+    }
+
     fn add_entry(&mut self, path: PathBuf) {
         self.add_entries(Some(vec![path]))
     }
@@ -257,6 +355,8 @@ impl XMODITS {
     fn new(flags: Config) -> (Self, Task<Message>) {
         let mut app = Self::default();
         app.load_cfg(flags);
+        app.resumable = ripper::Job::exists();
+        app.history = History::load();
 
         (
             app,
@@ -278,7 +378,10 @@ impl XMODITS {
         match message {
             Message::AboutPressed => self.view = View::About,
             Message::ConfigPressed => self.view = View::Configure,
+            Message::FiltersPressed => self.view = View::Filters,
+            Message::ProcessingPressed => self.view = View::Processing,
             Message::SettingsPressed => self.view = View::Settings,
+            Message::HistoryPressed => self.view = View::History,
             Message::Add(paths) => self.add_entries(paths),
             Message::Clear => self.clear_entries(),
             Message::DeleteSelected => self.delete_selected_entries(),
@@ -300,6 +403,16 @@ impl XMODITS {
                 return sample_ripping::update(&mut self.ripping_cfg, msg).map(Message::RippingCfg)
             }
             Message::NamingCfg(msg) => sample_naming::update(&mut self.naming_cfg, msg),
+            Message::ProcessingCfg(msg) => {
+                return processing::update(&mut self.ripping_cfg.processing, msg)
+                    .map(Message::ProcessingCfg)
+            }
+            Message::Filters(msg) => return self.filters.update(msg).map(Message::Filters),
+            Message::History(msg) => {
+                if let Some(record) = history::update(&mut self.history, msg) {
+                    return self.rip_again(record);
+                }
+            }
             Message::Open(link) => {
                 if let Err(err) = open::that_detached(link) {
                     tracing::warn!("Could not open external link: {:?}", err)
@@ -322,10 +435,14 @@ impl XMODITS {
             }
             Message::ProbeResult(probe) => self.tracker_info = probe,
             Message::SamplePlayer(msg) => {
+                if let Some((module, samples)) = self.sample_player.rip_request(&msg) {
+                    return self.rip_selected(module, samples);
+                }
+
                 return self
                     .sample_player
-                    .update(msg, &mut self.entries)
-                    .map(Message::SamplePlayer)
+                    .update(msg, &mut self.entries, &self.ripping_cfg, &self.naming_cfg)
+                    .map(Message::SamplePlayer);
             }
             Message::SaveConfig => {
                 return self.save_cfg();
@@ -343,10 +460,22 @@ impl XMODITS {
             Message::StartRipping => {
                 return self.start_ripping();
             }
+            Message::ResumeJob => {
+                return self.resume_job();
+            }
             Message::Cancel => {
+                self.state.set_paused(false);
                 self.state.set_message("Cancelling...");
                 self.ripper.cancel();
             }
+            Message::Pause => {
+                self.state.set_paused(true);
+                self.ripper.pause();
+            }
+            Message::Resume => {
+                self.state.set_paused(false);
+                self.ripper.resume();
+            }
             Message::Event(event) => match event {
                 event::Event::Clear => self.clear_entries(),
                 event::Event::Closed(id) => match Some(id) != self.main_id() {
@@ -394,6 +523,8 @@ impl XMODITS {
                         time,
                         destination,
                     };
+                    self.resumable = ripper::Job::exists();
+                    self.history = History::load();
                 }
             },
             Message::Ignore => (),
@@ -436,7 +567,10 @@ impl XMODITS {
 
         let top_left_menu = row![
             button("Ripping").on_press(Message::ConfigPressed),
+            button("Filters").on_press(Message::FiltersPressed),
+            button("Processing").on_press(Message::ProcessingPressed),
             button("Settings").on_press(Message::SettingsPressed),
+            button("History").on_press(Message::HistoryPressed),
             button("About").on_press(Message::AboutPressed),
         ]
         .spacing(5)
@@ -444,6 +578,10 @@ impl XMODITS {
         .align_y(Alignment::Center);
 
         let not_ripping = !self.state.is_ripping();
+        let can_start = not_ripping
+            && self.filters.error().is_none()
+            && self.naming_cfg.validate().is_ok()
+            && self.ripping_cfg.exported_format.is_available();
 
         let bottom_left_buttons = row![
             button(text_icon("Save Settings", icon::save()))
@@ -451,7 +589,7 @@ impl XMODITS {
                 .width(Length::FillPortion(2))
                 .padding(8),
             button(text_icon("START", icon::download()))
-                .on_press_maybe(not_ripping.then_some(Message::StartRipping))
+                .on_press_maybe(can_start.then_some(Message::StartRipping))
                 .style(style::button::start)
                 .width(Length::FillPortion(2))
                 .padding(8)
@@ -481,7 +619,20 @@ impl XMODITS {
                 .spacing(10)
                 .into()
             }
+            View::Filters => column![
+                self.filters.view().map(Message::Filters),
+                bottom_left_buttons,
+            ]
+            .spacing(10)
+            .into(),
+            View::Processing => column![
+                processing::view(&self.ripping_cfg.processing).map(Message::ProcessingCfg),
+                bottom_left_buttons,
+            ]
+            .spacing(10)
+            .into(),
             View::Settings => settings::view(&self.general_cfg).map(Message::GeneralCfg),
+            View::History => history::view(&self.history, !not_ripping).map(Message::History),
             View::About => about::view().map(Message::About),
         };
 
@@ -512,6 +663,11 @@ impl XMODITS {
             action("Add Folder", not_ripping.then_some(Message::FolderDialog)).padding(8),
             Space::with_width(Length::Fill)
         ]
+        .push_maybe(self.resumable.then(|| {
+            action("Resume Last Job", not_ripping.then_some(Message::ResumeJob))
+                .padding(8)
+                .style(style::button::start)
+        }))
         .push_maybe(
             (self.entries.total_selected() > 0 && (!self.entries.all_selected())).then(|| {
                 action(
@@ -536,7 +692,8 @@ impl XMODITS {
                 message,
                 progress,
                 errors,
-            } => ripping::view_ripping(message, *progress, *errors, show_gif),
+                paused,
+            } => ripping::view_ripping(message, *progress, *errors, *paused, show_gif),
             RippingState::Finished {
                 state,
                 time,
@@ -547,11 +704,40 @@ impl XMODITS {
         let allow_warnings = !self.general_cfg.suppress_warnings;
 
         let bad_cfg_warning = warning(
-            || allow_warnings && (!self.ripping_cfg.self_contained && !self.naming_cfg.prefix),
+            || {
+                allow_warnings
+                    && !self.ripping_cfg.self_contained
+                    && !self.naming_cfg.includes_module()
+                    && self.ripping_cfg.collision == Collision::Overwrite
+            },
             "\"Self Contained\" is disabled. \
-            You should enable \"Prefix Samples\" to reduce collisions. Unless you know what you are doing."
+            You should enable \"Prefix Samples\" or change what happens if a sample exists. \
+            Unless you know what you are doing."
         );
 
+        // Not a suggestion, so it can't be suppressed
+        let invalid_filters_error = self.filters.error().map(|error| {
+            text(format!(
+                "Can't start ripping, the regex filter is invalid: {error}"
+            ))
+            .style(style::text::error)
+        });
+
+        let invalid_template_error =
+            self.naming_cfg.validate().err().map(|error| {
+                text(format!("Can't start ripping. {error}")).style(style::text::error)
+            });
+
+        let unavailable_format_error = {
+            let format = self.ripping_cfg.exported_format;
+            (!format.is_available()).then(|| {
+                text(format!(
+                    "Can't start ripping. XMODITS was built without {format} support"
+                ))
+                .style(style::text::error)
+            })
+        };
+
         let too_many_files_warning = warning(
             || allow_warnings && self.entries.len() > 200,
             "That's a lot of files! You REALLY should be using folders.",
@@ -559,6 +745,9 @@ impl XMODITS {
 
         let right_half = column![destination, top_right_buttons, main_view]
             .push_maybe(bad_cfg_warning)
+            .push_maybe(invalid_filters_error)
+            .push_maybe(invalid_template_error)
+            .push_maybe(unavailable_format_error)
             .push_maybe(too_many_files_warning)
             .push(bottom_right_buttons)
             .width(Length::FillPortion(5))
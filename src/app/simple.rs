@@ -1,14 +1,15 @@
-use data::config::Config;
+use data::config::{Config, SampleRippingConfig};
 
 use crate::dialog;
 use crate::logger::write_error_log;
-use crate::ripper::subscription::extraction::strict_loading;
+use crate::ripper::extraction::error::Reason;
+use crate::ripper::extraction::{archive, extract, file_filter, parent, Deduplicator, Report};
+use crate::ripper::Signal;
 
 use std::cmp::Ordering;
 use std::path::PathBuf;
 
-use xmodits_lib::Error;
-use xmodits_lib::{extract, Ripper};
+use xmodits_lib::Ripper;
 
 pub fn rip(paths: impl IntoIterator<Item = String>) {
     let mut paths: Vec<PathBuf> = paths
@@ -29,15 +30,29 @@ pub fn rip(paths: impl IntoIterator<Item = String>) {
 
     let config = Config::load();
 
-    let filter = strict_loading(config.ripping.strict);
+    // Run the same checks as the GUI, an invalid template would otherwise be ignored.
+    let signal = Signal::new(
+        paths.clone(),
+        config.ripping.clone(),
+        config.naming.clone(),
+        config.filters.clone(),
+    );
+
+    if let Err(error) = signal.validate() {
+        dialog::critical_error(&error);
+        std::process::exit(2);
+    }
 
-    if config.ripping.strict {
-        paths.retain(|f| filter(f));
+    let filter = match file_filter(config.ripping.strict, &config.filters) {
+        Ok(filter) => filter,
+        Err(error) => return dialog::critical_error(&error),
+    };
 
-        if paths.is_empty() {
-            return dialog::no_valid_modules();
-        };
-    }
+    paths.retain(|f| filter(f, parent(f)));
+
+    if paths.is_empty() {
+        return dialog::no_valid_modules();
+    };
 
     let use_cwd = config.general.non_gui_use_cwd;
 
@@ -51,21 +66,32 @@ pub fn rip(paths: impl IntoIterator<Item = String>) {
 
     let log_path = config.general.logging_path.as_ref().unwrap_or(&destination);
 
-    let self_contained = config.ripping.self_contained;
+    let ripping = SampleRippingConfig {
+        destination: destination.clone(),
+        ..config.ripping.clone()
+    };
+
+    let dedup = Deduplicator::new(&ripping);
 
-    let mut ripper = Ripper {
+    let ripper = Ripper {
         namer_func: config.naming.build_func(),
         format: config.ripping.exported_format.get_impl()
     };
 
-    let errors: Vec<(PathBuf, Error)> = paths
-        .into_iter()
-        .filter_map(|mod_path| {
-            extract(&mod_path, &destination, &ripper, self_contained)
-                .err()
-                .map(|error| (mod_path, error))
-        })
-        .collect();
+    let mut errors: Vec<(PathBuf, Reason)> = Vec::new();
+
+    let mut collect = |report: Report| {
+        if let Some(failed) = report.failed {
+            errors.push((failed.path, failed.reason));
+        }
+    };
+
+    for mod_path in paths {
+        match archive::is_archive(&mod_path) {
+            true => archive::extract(&mod_path, &ripper, &ripping, &dedup, &mut collect),
+            false => collect(extract(&mod_path, &ripper, &ripping, &dedup)),
+        }
+    }
 
     let quiet_output = config.general.non_gui_quiet_output;
 
@@ -1,7 +1,11 @@
+mod headless;
+mod report;
+
 use std::path::Path;
 use std::process::exit;
 
 static HELP: &str = "\
+rip <paths...>      Rips samples without launching the GUI (see 'rip --help')
 --help      -h      Prints help information
 --version   -V      Prints version
 ";
@@ -19,9 +23,14 @@ pub enum Mode {
     Unrecognised(String),
     #[cfg(feature = "manual")]
     Manual,
+    Rip(Vec<String>),
 }
 
 pub fn parse(args: Vec<String>) -> Mode {
+    if args.first().is_some_and(|f| f == "rip") {
+        return Mode::Rip(args[1..].to_vec());
+    }
+
     if contains(&args, ["--help", "-h"]) {
         return Mode::Help;
     }
@@ -86,6 +95,10 @@ pub fn print_unrecognised(option: String) -> ! {
     print_help()
 }
 
+pub fn rip(args: Vec<String>) -> ! {
+    headless::rip(args)
+}
+
 #[cfg(feature = "manual")]
 pub fn print_manual() -> ! {
     print!("{}", data::MANUAL);
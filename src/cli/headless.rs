@@ -0,0 +1,472 @@
+//! Rip samples without launching the GUI.
+//!
+//! Any option that isn't provided will fall back to the saved configuration.
+
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::str::FromStr;
+
+use data::config::processing::{BitDepth, Channels, Normalize};
+use data::config::sample_ripping::{Collision, Dedup, Noise};
+use data::config::{Config, SampleNameConfig, SampleRippingConfig};
+use data::history::{self, Outcome};
+use data::Time;
+use data::ExportFormat;
+
+use super::report::{Record, Summary};
+use crate::ripper::extraction::{self, Message, StopMessage};
+use crate::ripper::{self, Job, Signal};
+use crate::screen::config::sample_naming::{self, Message as Naming};
+
+pub static HELP: &str = "\
+Usage: xmodits rip <paths...> [options]
+
+Options that are not provided will use the saved configuration.
+
+--output            -o  <dir>       Destination folder
+--format            -f  <format>    Export format (wav, aiff, its, s3i, 8svx, raw, flac, ogg)
+--depth             -d  <n>         Folder scan depth
+--threads           -t  <n>         Worker threads (0 = automatic)
+--self-contained    / --no-self-contained
+--strict            / --no-strict
+--index-only        / --no-index-only
+--index-raw         / --no-index-raw
+--index-padding         <n>         Minimum digits of a sample index (1-4)
+--upper             / --no-upper
+--lower             / --no-lower
+--prefix            / --no-prefix
+--prefer-filename   / --no-prefer-filename
+--collision             <policy>    If a sample already exists: overwrite, skip, rename or error
+--sanitize          / --no-sanitize Replace characters that can't be used on Windows, FAT or exFAT
+--tags              / --no-tags     Embed the module, source path and sample details in WAV, AIFF and FLAC files
+--sfz               / --no-sfz      Write an SFZ instrument for every module
+--sf2               / --no-sf2      Write a SoundFont 2 bank for every module
+--manifest          / --no-manifest Write a manifest.json of the samples ripped from every module
+--dedup                 <mode>      Identical samples: off, skip, hardlink or manifest
+--noise                 <action>    Samples that are most likely static noise: keep, skip or quarantine
+--dc-offset         / --no-dc-offset Remove the DC offset of every sample
+--trim              / --no-trim     Trim silence from the start and end of samples (loops are kept)
+--silence               <dB>        Anything quieter than this is trimmed (default -60)
+--normalize             <mode>      Normalize samples: off, peak or rms
+--level                 <dB>        Level that samples are normalized to (default -1)
+--channels              <mode>      Convert samples: keep, mono or stereo
+--fade-in           / --no-fade-in
+--fade-out          / --no-fade-out Looped samples never fade out
+--fade                  <ms>        Length of a fade (default 10)
+--rate                  <hz>        Resample samples to a sample rate (0 = keep)
+--bits                  <depth>     Convert samples to a bit depth: keep, 8 or 16
+--dither            / --no-dither   Dither samples when the bit depth is reduced
+--template              <format>    Name samples with a template, e.g. \"{index:02}_{name}.{ext}\"
+--no-template
+
+--resume                            Continue the last job that was cancelled or interrupted.
+                                    The job's paths and options are used.
+
+--json                              Print a JSON record per module instead of the progress
+--report                <file>      Write the results of every module to a JSON file
+";
+
+/// Options parsed from the command line
+pub struct Options {
+    pub signal: Signal,
+    /// Print a JSON record per module to stdout
+    pub json: bool,
+    /// Write the results to a JSON file
+    pub report: Option<PathBuf>,
+}
+
+/// Rip the provided entries and exit.
+///
+/// Exits with 0 if every module was ripped, 1 if any module failed or the rip was stopped,
+/// and 2 if the arguments are invalid.
+pub fn rip(args: Vec<String>) -> ! {
+    if args.iter().any(|f| matches!(f.as_str(), "--help" | "-h")) {
+        print!("{HELP}");
+        exit(0)
+    }
+
+    let options = match parse(args, Config::load()) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}\n");
+            eprint!("{HELP}");
+            exit(2)
+        }
+    };
+
+    let destination = &options.signal.ripping.destination;
+
+    if let Err(error) = std::fs::create_dir_all(destination) {
+        eprintln!(
+            "Could not create destination folder '{}': {error}",
+            destination.display()
+        );
+        exit(2)
+    }
+
+    exit(run(options))
+}
+
+/// Construct a start signal from the command line arguments.
+///
+/// Options that are not provided are taken from the given configuration.
+pub fn parse(args: Vec<String>, config: Config) -> Result<Options, String> {
+    let mut ripping: SampleRippingConfig = config.ripping;
+    let mut naming: SampleNameConfig = config.naming;
+    let filters = config.filters;
+    let mut entries: Vec<PathBuf> = Vec::new();
+    let mut json = false;
+    let mut report = None;
+    let mut resume = false;
+    // The first argument that can't be used with "--resume"
+    let mut overrides = None;
+
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if overrides.is_none() && !matches!(arg.as_str(), "--resume" | "--json" | "--report") {
+            overrides = Some(arg.clone());
+        }
+
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Option '{arg}' requires a value"))
+        };
+
+        match arg.as_str() {
+            "--output" | "-o" => ripping.destination = value()?.into(),
+            "--format" | "-f" => ripping.exported_format = format(&value()?)?,
+            "--depth" | "-d" => ripping.folder_max_depth = number(&arg, &value()?)?,
+            "--threads" | "-t" => ripping.worker_threads = number(&arg, &value()?)?,
+            "--self-contained" => ripping.self_contained = true,
+            "--no-self-contained" => ripping.self_contained = false,
+            "--collision" => ripping.collision = collision(&value()?)?,
+            "--dedup" => ripping.dedup = dedup(&value()?)?,
+            "--sanitize" => ripping.sanitize = true,
+            "--no-sanitize" => ripping.sanitize = false,
+            "--tags" => ripping.embed_tags = true,
+            "--no-tags" => ripping.embed_tags = false,
+            "--sfz" => ripping.sfz = true,
+            "--no-sfz" => ripping.sfz = false,
+            "--sf2" => ripping.soundfont = true,
+            "--no-sf2" => ripping.soundfont = false,
+            "--manifest" => ripping.manifest = true,
+            "--no-manifest" => ripping.manifest = false,
+            "--noise" => ripping.noise = noise(&value()?)?,
+            "--dc-offset" => ripping.processing.remove_dc_offset = true,
+            "--no-dc-offset" => ripping.processing.remove_dc_offset = false,
+            "--trim" => ripping.processing.trim_silence = true,
+            "--no-trim" => ripping.processing.trim_silence = false,
+            "--silence" => ripping.processing.silence_threshold = number(&arg, &value()?)?,
+            "--normalize" => ripping.processing.normalize = normalize(&value()?)?,
+            "--level" => ripping.processing.normalize_level = number(&arg, &value()?)?,
+            "--channels" => ripping.processing.channels = channels(&value()?)?,
+            "--fade-in" => ripping.processing.fade_in = true,
+            "--no-fade-in" => ripping.processing.fade_in = false,
+            "--fade-out" => ripping.processing.fade_out = true,
+            "--no-fade-out" => ripping.processing.fade_out = false,
+            "--fade" => ripping.processing.fade_length = number(&arg, &value()?)?,
+            "--rate" => ripping.processing.sample_rate = number(&arg, &value()?)?,
+            "--bits" => ripping.processing.bit_depth = bit_depth(&value()?)?,
+            "--dither" => ripping.processing.dither = true,
+            "--no-dither" => ripping.processing.dither = false,
+            "--strict" => ripping.strict = true,
+            "--no-strict" => ripping.strict = false,
+            "--index-padding" => {
+                let padding = number(&arg, &value()?)?;
+                sample_naming::update(&mut naming, Naming::IndexPadding(padding))
+            }
+            "--index-only" => sample_naming::update(&mut naming, Naming::IndexOnly(true)),
+            "--no-index-only" => sample_naming::update(&mut naming, Naming::IndexOnly(false)),
+            "--index-raw" => sample_naming::update(&mut naming, Naming::IndexRaw(true)),
+            "--no-index-raw" => sample_naming::update(&mut naming, Naming::IndexRaw(false)),
+            "--upper" => sample_naming::update(&mut naming, Naming::UpperCase(true)),
+            "--no-upper" => sample_naming::update(&mut naming, Naming::UpperCase(false)),
+            "--lower" => sample_naming::update(&mut naming, Naming::LowerCase(true)),
+            "--no-lower" => sample_naming::update(&mut naming, Naming::LowerCase(false)),
+            "--prefix" => sample_naming::update(&mut naming, Naming::PrefixSamples(true)),
+            "--no-prefix" => sample_naming::update(&mut naming, Naming::PrefixSamples(false)),
+            "--prefer-filename" => sample_naming::update(&mut naming, Naming::PreferFilename(true)),
+            "--no-prefer-filename" => {
+                sample_naming::update(&mut naming, Naming::PreferFilename(false))
+            }
+            "--template" => {
+                naming.template = value()?;
+                naming.use_template = true;
+            }
+            "--no-template" => naming.use_template = false,
+            "--resume" => resume = true,
+            "--json" => json = true,
+            "--report" => report = Some(PathBuf::from(value()?)),
+            flag if flag.starts_with('-') && !Path::new(flag).exists() => {
+                return Err(format!("Unrecognised option '{flag}'"));
+            }
+            path => entries.push(PathBuf::from(path)),
+        }
+    }
+
+    if resume {
+        // The job keeps its own entries and options
+        if let Some(arg) = overrides {
+            return Err(format!("'{arg}' can't be used with '--resume'"));
+        }
+
+        let job = Job::load().ok_or("There isn't a job to resume")?;
+        let signal = Signal::from(job);
+        signal.validate()?;
+
+        return Ok(Options {
+            signal,
+            json,
+            report,
+        });
+    }
+
+    if entries.is_empty() {
+        return Err("No files or folders were provided".into());
+    }
+
+    if let Some(missing) = entries.iter().find(|f| !f.exists()) {
+        return Err(format!("'{}' does not exist", missing.display()));
+    }
+
+    let signal = Signal::new(entries, ripping, naming, filters);
+    signal.validate()?;
+
+    Ok(Options {
+        signal,
+        json,
+        report,
+    })
+}
+
+/// Rip the samples on the current thread, printing the progress to stdout.
+///
+/// If ``json`` is set, only the records are printed to stdout.
+///
+/// Returns the exit code.
+fn run(options: Options) -> i32 {
+    let Options {
+        signal,
+        json,
+        report,
+    } = options;
+
+    let destination = signal.ripping.destination.clone();
+    let job = Job::new(&signal);
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let mut timer = Time::init();
+
+    std::thread::spawn(move || extraction::rip(tx, signal));
+
+    let mut total: u64 = 0;
+    let mut progress: u64 = 0;
+    let mut errors: u64 = 0;
+    let mut stopped = false;
+    let mut outcome = Outcome::NoErrors;
+    let mut records: Vec<Record> = Vec::new();
+
+    while let Some(message) = rx.blocking_recv() {
+        match message {
+            Message::SetTotal(new_total) => {
+                total = new_total;
+                progress = 0;
+            }
+            Message::Found(found) => total += found,
+            Message::Info(Some(info)) if json => eprintln!("{info}"),
+            Message::Info(Some(info)) => println!("{info}"),
+            Message::Info(None) => (),
+            Message::Progress(module) => {
+                progress += 1;
+
+                if !json {
+                    for path in &module.renamed {
+                        println!("Renamed: {}", path.display());
+                    }
+
+                    for path in &module.skipped {
+                        println!("Skipped (already exists): {}", path.display());
+                    }
+
+                    for (path, original) in &module.duplicates {
+                        println!("Duplicate: {} -> {}", path.display(), original.display());
+                    }
+
+                    for path in &module.noise {
+                        println!("Static noise: {}", path.display());
+                    }
+                }
+
+                if let Some(failed) = &module.failed {
+                    errors += 1;
+                    eprintln!("{failed}");
+                }
+
+                if json || report.is_some() {
+                    let record = Record::from(&module);
+
+                    if json {
+                        match serde_json::to_string(&record) {
+                            Ok(line) => println!("{line}"),
+                            Err(error) => eprintln!("Could not serialize record: {error}"),
+                        }
+                    }
+
+                    if report.is_some() {
+                        records.push(record);
+                    }
+                }
+
+                if !json {
+                    println!("[{progress}/{total}] Errors: {errors}");
+                }
+            }
+            Message::Done => break,
+            Message::Stop(stop) => {
+                stopped = true;
+                match stop {
+                    StopMessage::Cancel => {
+                        outcome = Outcome::Cancelled;
+                        eprintln!("Cancelled")
+                    }
+                    StopMessage::Abort => {
+                        outcome = Outcome::Aborted;
+                        eprintln!("Ripping process was aborted because of an internal error.")
+                    }
+                }
+                break;
+            }
+        }
+    }
+
+    timer.stop();
+
+    let mut error_log = None;
+
+    if let Some(path) = report {
+        let summary = Summary {
+            destination: destination.display().to_string(),
+            elapsed: timer.elapsed() as f64,
+            total: progress,
+            errors,
+            stopped,
+            modules: records,
+        };
+
+        match summary.save(&path) {
+            Ok(()) => {
+                eprintln!("Report saved to: {}", path.display());
+                error_log = Some(path);
+            }
+            Err(error) => eprintln!("Could not save report to '{}': {error}", path.display()),
+        }
+    }
+
+    if outcome == Outcome::NoErrors && errors > 0 {
+        outcome = Outcome::Errors { total: errors };
+    }
+
+    // The report is the closest thing to an error log
+    let error_log = error_log.filter(|_| errors > 0);
+
+    ripper::history::add(history::Record::new(
+        job.entries,
+        job.ripping,
+        job.naming,
+        job.filters,
+        job.samples,
+        outcome,
+        &timer,
+        error_log,
+    ));
+
+    match json {
+        true => eprintln!("{timer}"),
+        false => {
+            println!("{timer}");
+            println!("Samples were ripped to: {}", destination.display());
+        }
+    }
+
+    match errors {
+        0 if !stopped => 0,
+        0 => 1,
+        n => {
+            eprintln!("{n} module(s) could not be ripped");
+            1
+        }
+    }
+}
+
+fn format(input: &str) -> Result<ExportFormat, String> {
+    data::SUPPORTED_FORMATS
+        .iter()
+        .find(|format| {
+            format.get_impl().extension().eq_ignore_ascii_case(input)
+                || format.to_string().eq_ignore_ascii_case(input)
+        })
+        .copied()
+        .ok_or_else(|| format!("Unsupported export format '{input}'"))
+}
+
+fn collision(input: &str) -> Result<Collision, String> {
+    Collision::ALL
+        .iter()
+        .find(|policy| policy.to_string().eq_ignore_ascii_case(input))
+        .copied()
+        .ok_or_else(|| format!("Unknown collision policy '{input}'"))
+}
+
+fn dedup(input: &str) -> Result<Dedup, String> {
+    Dedup::ALL
+        .iter()
+        .find(|mode| {
+            mode.to_string()
+                .replace(' ', "")
+                .eq_ignore_ascii_case(input)
+        })
+        .copied()
+        .ok_or_else(|| format!("Unknown dedup mode '{input}'"))
+}
+
+fn noise(input: &str) -> Result<Noise, String> {
+    Noise::ALL
+        .iter()
+        .find(|action| action.to_string().eq_ignore_ascii_case(input))
+        .copied()
+        .ok_or_else(|| format!("Unknown noise action '{input}'"))
+}
+
+fn normalize(input: &str) -> Result<Normalize, String> {
+    Normalize::ALL
+        .iter()
+        .find(|mode| mode.to_string().eq_ignore_ascii_case(input))
+        .copied()
+        .ok_or_else(|| format!("Unknown normalization '{input}'"))
+}
+
+fn channels(input: &str) -> Result<Channels, String> {
+    Channels::ALL
+        .iter()
+        .find(|channels| channels.to_string().eq_ignore_ascii_case(input))
+        .copied()
+        .ok_or_else(|| format!("Unknown channel conversion '{input}'"))
+}
+
+fn bit_depth(input: &str) -> Result<BitDepth, String> {
+    BitDepth::ALL
+        .iter()
+        .find(|depth| match depth.bits() {
+            Some(bits) => input == bits.to_string() || input == depth.to_string(),
+            None => depth.to_string().eq_ignore_ascii_case(input),
+        })
+        .copied()
+        .ok_or_else(|| format!("Unknown bit depth '{input}'"))
+}
+
+fn number<T: FromStr>(flag: &str, input: &str) -> Result<T, String> {
+    input
+        .parse::<T>()
+        .map_err(|_| format!("Option '{flag}' expects a number, got '{input}'"))
+}
@@ -0,0 +1,135 @@
+//! Machine readable results of a headless rip
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::ripper::extraction::error::Reason;
+use crate::ripper::extraction::Report;
+
+/// The outcome of ripping a single module.
+#[derive(Serialize, Debug)]
+pub struct Record {
+    pub source: String,
+    pub destination: String,
+    pub samples: Vec<String>,
+    /// Samples that were renamed to avoid a collision
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub renamed: Vec<String>,
+    /// Samples that weren't written because the file already exists
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+    /// Number of sample names that had characters replaced
+    pub sanitized: usize,
+    /// Samples that were already ripped
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<Duplicate>,
+    /// Samples that are most likely static noise
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub noise: Vec<String>,
+    /// Elapsed time in seconds
+    pub elapsed: f64,
+    pub status: Status,
+    /// Why the module could not be ripped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Samples that could not be ripped
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<SampleError>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    /// Every sample was ripped
+    Ripped,
+    /// Some samples could not be ripped
+    Partial,
+    /// The module could not be ripped
+    Failed,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SampleError {
+    pub raw_index: usize,
+    pub reason: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Duplicate {
+    pub path: String,
+    /// The first copy of the sample
+    pub original: String,
+}
+
+impl From<&Report> for Record {
+    fn from(report: &Report) -> Self {
+        let (status, error, errors) = match report.failed.as_ref().map(|f| &f.reason) {
+            None => (Status::Ripped, None, Vec::new()),
+            Some(Reason::Single(reason)) => (Status::Failed, Some(reason.to_owned()), Vec::new()),
+            Some(Reason::Multiple(reasons)) => (
+                Status::Partial,
+                None,
+                reasons
+                    .iter()
+                    .map(|(raw_index, reason)| SampleError {
+                        raw_index: *raw_index,
+                        reason: reason.to_owned(),
+                    })
+                    .collect(),
+            ),
+        };
+
+        Self {
+            source: report.source.display().to_string(),
+            destination: report.destination.display().to_string(),
+            samples: paths(&report.exported),
+            renamed: paths(&report.renamed),
+            skipped: paths(&report.skipped),
+            sanitized: report.sanitized,
+            duplicates: report
+                .duplicates
+                .iter()
+                .map(|(path, original)| Duplicate {
+                    path: path.display().to_string(),
+                    original: original.display().to_string(),
+                })
+                .collect(),
+            noise: paths(&report.noise),
+            elapsed: report.elapsed.as_secs_f64(),
+            status,
+            error,
+            errors,
+        }
+    }
+}
+
+fn paths(paths: &[PathBuf]) -> Vec<String> {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect()
+}
+
+/// Summary of the whole rip, written with ``--report``
+#[derive(Serialize, Debug)]
+pub struct Summary {
+    pub destination: String,
+    /// Elapsed time in seconds
+    pub elapsed: f64,
+    pub total: u64,
+    pub errors: u64,
+    pub stopped: bool,
+    pub modules: Vec<Record>,
+}
+
+impl Summary {
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.flush()?;
+        Ok(())
+    }
+}
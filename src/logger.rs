@@ -17,10 +17,11 @@ pub fn init_logging() {
     use tracing_subscriber::FmtSubscriber;
 
     // all spans/events with a level higher than TRACE (e.g, debug, info, warn, etc.)
-    // will be written to stdout.
+    // will be written to stderr, so that stdout is left for headless ripping.
     set_global_default(
         FmtSubscriber::builder()
             .with_max_level(Level::DEBUG)
+            .with_writer(std::io::stderr)
             .finish(),
     )
     .expect("setting default subscriber failed");
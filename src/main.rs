@@ -42,6 +42,7 @@ fn main() -> iced::Result {
         Mode::BuildInfo => cli::print_info(),
         #[cfg(feature = "manual")]
         Mode::Manual => cli::print_manual(),
+        Mode::Rip(args) => cli::rip(args),
         Mode::Unrecognised(option) => cli::print_unrecognised(option),
     }
 }
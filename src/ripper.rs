@@ -2,11 +2,14 @@
 
 pub mod extraction;
 pub mod handle;
+pub mod history;
+pub mod journal;
 pub mod signal;
 pub mod stop_flag;
 pub mod subscription;
 
-pub use extraction::strict_loading;
+pub use extraction::{file_filter, strict_loading};
 pub use handle::Handle;
+pub use journal::Job;
 pub use signal::Signal;
 pub use subscription::{Message, subscription};
\ No newline at end of file
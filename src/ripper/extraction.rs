@@ -1,19 +1,30 @@
+pub mod archive;
 pub mod buffer;
+pub mod dedup;
 pub mod error;
 pub mod error_handler;
+pub mod export;
+pub mod manifest;
+pub mod sanitize;
+pub mod sniff;
+pub mod wrapper;
 
 pub use buffer::{Batch, Buffer};
+pub use dedup::Deduplicator;
 pub use error::Failed;
 pub use error_handler::ErrorHandler;
+pub use export::{extract, Report};
 
-use crate::logger;
-
+use super::journal::Journal;
 use super::stop_flag;
 use super::Signal;
 
-use data::config::SampleRippingConfig;
+use data::config::filters::Filter;
+use data::config::{FilterConfig, SampleRippingConfig};
+use data::history::Selection;
 use xmodits_lib::Ripper;
 
+use std::collections::BTreeSet;
 use std::fs::File;
 use std::io::{BufRead, BufReader, BufWriter, Seek, Write};
 use std::path::{Path, PathBuf};
@@ -27,7 +38,9 @@ use walkdir::WalkDir;
 pub enum Message {
     SetTotal(u64),
     Info(Option<String>),
-    Progress(Option<Failed>),
+    Progress(Report),
+    /// An archive contains more modules than it was counted for
+    Found(u64),
     Done,
     Stop(StopMessage),
 }
@@ -57,6 +70,19 @@ fn split_files_folders(paths: Vec<PathBuf>) -> (Vec<PathBuf>, Vec<PathBuf>) {
 }
 
 pub fn rip(tx: AsyncSender<Message>, signal: Signal) {
+    // Signals are validated before they're sent, but an invalid filter would rip every file
+    let filter = match file_filter(signal.ripping.strict, &signal.filters) {
+        Ok(filter) => filter,
+        Err(error) => {
+            tracing::error!("{error}");
+            let _ = tx.send(Message::info(error));
+            let _ = tx.send(Message::Stop(StopMessage::Abort));
+            return;
+        }
+    };
+
+    let journal = Arc::new(Journal::open(&signal));
+    let samples = signal.samples;
     let (files, folders) = split_files_folders(signal.entries);
 
     let mut cfg = signal.ripping;
@@ -68,17 +94,44 @@ pub fn rip(tx: AsyncSender<Message>, signal: Signal) {
 
     let ripper = Arc::new(Ripper::new(
         signal.naming.build_func(),
-        cfg.exported_format.into(),
+        cfg.exported_format.get_impl(),
     ));
 
+    let dedup = Arc::new(Deduplicator::new(&cfg));
+
     // Create the destination folder if it doesn't exist
     let _ = std::fs::create_dir(&cfg.destination);
 
-    stage_1(tx.clone(), files, ripper.clone(), &cfg);
-    stage_2(tx.clone(), folders, ripper, cfg);
+    stage_1(
+        tx.clone(),
+        files,
+        ripper.clone(),
+        &cfg,
+        &dedup,
+        &journal,
+        &samples,
+        &filter,
+    );
+    stage_2(
+        tx.clone(),
+        folders,
+        ripper,
+        cfg,
+        dedup,
+        journal.clone(),
+        &filter,
+    );
 
-    tx.send(match stop_flag::get_flag() {
-        stop_flag::StopFlag::None => Message::Done,
+    let flag = stop_flag::get_flag();
+
+    // Keep the journal if the rip was stopped so that it can be resumed
+    if matches!(flag, stop_flag::StopFlag::None | stop_flag::StopFlag::Pause) {
+        journal.finish();
+    }
+
+    tx.send(match flag {
+        // The rip could be paused after the last module was ripped
+        stop_flag::StopFlag::None | stop_flag::StopFlag::Pause => Message::Done,
         stop_flag::StopFlag::Cancel => Message::Stop(StopMessage::Cancel),
         stop_flag::StopFlag::Abort => Message::Stop(StopMessage::Abort),
     })
@@ -90,7 +143,17 @@ fn stage_1(
     files: Vec<PathBuf>,
     ripper: Arc<Ripper>,
     cfg: &SampleRippingConfig,
+    dedup: &Deduplicator,
+    journal: &Journal,
+    samples: &Selection,
+    filter: &impl Fn(&Path, &Path) -> bool,
 ) {
+    let files: Vec<PathBuf> = files
+        .into_iter()
+        .filter(|file| !journal.is_completed(file) && filter(file, parent(file)))
+        .collect();
+
+    // Only count the files that will be ripped
     if files.is_empty() {
         return;
     }
@@ -101,18 +164,19 @@ fn stage_1(
     let info = format!("Stage 1: Ripping {} files...", files.len());
     subscr_tx.send(Message::info(info)).unwrap();
 
-    let filter = strict_loading(cfg.strict);
+    for file in &files {
+        stop_flag::wait_while_paused();
 
-    for file in files.iter().filter(|f| filter(f)) {
         if stop_flag::is_set() {
             break;
         }
 
-        let _ = subscr_tx.send(Message::Progress(
-            extract(file, &cfg.destination, ripper.as_ref(), cfg.self_contained)
-                .map_err(|error| Failed::new(file.display().to_string(), error))
-                .err(),
-        ));
+        let selected = samples.get(file);
+
+        // Files that failed are retried when the job is resumed
+        if rip_file(file, selected, ripper.as_ref(), cfg, dedup, &subscr_tx) {
+            journal.complete(file);
+        }
     }
 }
 
@@ -123,21 +187,45 @@ fn stage_2(
     folders: Vec<PathBuf>,
     ripper: Arc<Ripper>,
     cfg: SampleRippingConfig,
+    dedup: Arc<Deduplicator>,
+    journal: Arc<Journal>,
+    filter: &impl Fn(&Path, &Path) -> bool,
 ) {
     if folders.is_empty() || stop_flag::is_set() {
         return;
     }
     let selected_dirs = folders.len();
-    subscr_tx
-        .send(Message::info("Traversing Directories..."))
-        .unwrap();
 
-    let filter = strict_loading(cfg.strict);
+    let (mut file, lines) = match journal.previous_traversal() {
+        Some(file) => {
+            subscr_tx.send(Message::info("Resuming...")).unwrap();
+            remaining(file, &journal)
+        }
+        None => {
+            subscr_tx
+                .send(Message::info("Traversing Directories..."))
+                .unwrap();
+
+            let output = journal
+                .traversal_file()
+                .expect("Creating a file to store the traversed entries");
+
+            // Files that were ripped by a previous attempt aren't counted
+            let pending =
+                |path: &Path, root: &Path| !journal.is_completed(path) && filter(path, root);
+
+            let traversed = traverse(folders, cfg.folder_max_depth, pending, output, |lines| {
+                let info = format!("Traversing Directories...\n({lines} filtered files)");
+                subscr_tx.send(Message::info(info)).unwrap()
+            });
+
+            if !stop_flag::is_set() {
+                journal.set_traversed();
+            }
 
-    let (mut file, lines) = traverse(folders, cfg.folder_max_depth, filter, |lines| {
-        let info = format!("Traversing Directories...\n({lines} filtered files)");
-        subscr_tx.send(Message::info(info)).unwrap()
-    });
+            traversed
+        }
+    };
 
     subscr_tx.send(Message::SetTotal(lines)).unwrap();
 
@@ -160,7 +248,56 @@ fn stage_2(
         return;
     }
 
-    Batcher::new(&mut file, batch_size(lines), ripper, cfg, subscr_tx).start();
+    Batcher::new(
+        &mut file,
+        batch_size(lines),
+        ripper,
+        cfg,
+        dedup,
+        journal,
+        subscr_tx,
+    )
+    .start();
+}
+
+/// Rip a module, or every module in an archive.
+///
+/// If ``selected`` is provided, only those samples are ripped from the module.
+/// An archive is only counted once, so the subscription is told about any extra modules.
+///
+/// Returns true if every module was ripped without any errors.
+fn rip_file(
+    file: &Path,
+    selected: Option<&BTreeSet<usize>>,
+    ripper: &Ripper,
+    cfg: &SampleRippingConfig,
+    dedup: &Deduplicator,
+    subscr_tx: &AsyncSender<Message>,
+) -> bool {
+    if !archive::is_archive(file) {
+        let report = match selected {
+            Some(selected) => export::extract_selected(file, selected, ripper, cfg, dedup),
+            None => extract(file, ripper, cfg, dedup),
+        };
+        let ok = report.is_ok();
+        let _ = subscr_tx.send(Message::Progress(report));
+        return ok;
+    }
+
+    let mut counted = false;
+    let mut ok = true;
+
+    archive::extract(file, ripper, cfg, dedup, |report| {
+        if std::mem::replace(&mut counted, true) {
+            let _ = subscr_tx.send(Message::Found(1));
+        }
+
+        ok &= report.is_ok();
+        let _ = subscr_tx.send(Message::Progress(report));
+    });
+
+    // The rest of the archive wasn't ripped if the rip was stopped
+    ok && !stop_flag::is_set()
 }
 
 fn batch_size(lines: u64) -> usize {
@@ -174,39 +311,29 @@ fn batch_size(lines: u64) -> usize {
     }
 }
 
-fn extract(
-    file: impl AsRef<Path>,
-    destination: &Path,
-    ripper: &Ripper,
-    self_contained: bool,
-) -> Result<(), xmodits_lib::Error> {
-    logger::log_file_on_panic(file.as_ref(), |file| {
-        xmodits_lib::extract(file, destination, ripper, self_contained)
-    })
-}
-
 /// Traversing deeply nested directories can use a lot of memory.
 ///
 /// For that reason we write the output to a file
 fn traverse(
     dirs: Vec<PathBuf>,
     max_depth: u8,
-    filter: impl Fn(&Path) -> bool,
+    filter: impl Fn(&Path, &Path) -> bool,
+    output: File,
     callback: impl Fn(u64),
 ) -> (BufReader<File>, u64) {
-    let mut file = tempfile::tempfile()
-        .map(BufWriter::new)
-        .expect("Creating a temporary file");
+    let mut file = BufWriter::new(output);
 
     // store the number of entries
     let mut lines: u64 = 0;
 
     // traverse list of directories, output to a file
     'traversal: for folder in dirs.into_iter() {
-        for entry in WalkDir::new(folder)
+        for entry in WalkDir::new(&folder)
             .max_depth(max_depth as usize)
             .into_iter()
         {
+            stop_flag::wait_while_paused();
+
             if stop_flag::is_set() {
                 break 'traversal;
             }
@@ -215,7 +342,7 @@ fn traverse(
                 continue;
             };
 
-            if f.path().is_file() && filter(f.path()) {
+            if f.path().is_file() && filter(f.path(), &folder) {
                 lines += 1;
                 callback(lines);
                 file.write_fmt(format_args!("{}\n", f.path().display()))
@@ -233,6 +360,22 @@ fn traverse(
     (BufReader::new(file), lines)
 }
 
+/// Count the entries of a previous traversal that haven't been ripped yet
+fn remaining(file: File, journal: &Journal) -> (BufReader<File>, u64) {
+    let mut file = BufReader::new(file);
+
+    let lines = (&mut file)
+        .lines()
+        .map_while(Result::ok)
+        .filter(|line| !journal.is_completed(Path::new(line)))
+        .count() as u64;
+
+    // Rewind cursor to beginning
+    file.rewind().unwrap();
+
+    (file, lines)
+}
+
 #[derive(Copy, Clone)]
 struct NextBatch;
 
@@ -251,6 +394,7 @@ struct Batcher<'io> {
     buffer: Buffer<String>,
     batch_tx: Sender<Batch<String>>,
     worker_rx: Receiver<NextBatch>,
+    journal: Arc<Journal>,
 }
 
 impl<'io> Batcher<'io> {
@@ -259,6 +403,8 @@ impl<'io> Batcher<'io> {
         batch_size: usize,
         ripper: Arc<Ripper>,
         cfg: SampleRippingConfig,
+        dedup: Arc<Deduplicator>,
+        journal: Arc<Journal>,
         subscr_tx: AsyncSender<Message>,
     ) -> Batcher<'io> {
         let (batch_tx, batch_rx) = mpsc::channel::<Batch<String>>();
@@ -272,6 +418,7 @@ impl<'io> Batcher<'io> {
             buffer: Buffer::init(batch_size),
             batch_tx,
             worker_rx,
+            journal: journal.clone(),
         };
 
         // load first buffer
@@ -281,28 +428,31 @@ impl<'io> Batcher<'io> {
         {
             use rayon::prelude::*;
 
-            let destination = cfg.destination;
-            let self_contained = cfg.self_contained;
+            let worker_threads = cfg.worker_threads;
 
             rayon::ThreadPoolBuilder::new()
                 .thread_name(|index| format!("XMODITS Ripping Thread - {index}"))
-                .num_threads(cfg.worker_threads)
+                .num_threads(worker_threads)
                 .panic_handler(|_| {/* Don't abort process */})
                 .build()
                 .expect("constructing thread pool")
                 .spawn(move || {
                     while let Ok(batch) = batch_rx.recv() {
                         batch.lock().par_iter().for_each(|file| {
+                            // Finish the current module before pausing
+                            stop_flag::wait_while_paused();
+
                             if stop_flag::is_set() {
                                 return;
                             }
 
                             // Send an update to the subscription
-                            let _ = subscr_tx.send(Message::Progress(
-                                extract(file, &destination, &ripper, self_contained)
-                                    .err()
-                                    .map(|error| Failed::new(file.into(), error)),
-                            ));
+                            let file = Path::new(file);
+
+                            // Files that failed are retried when the job is resumed
+                            if rip_file(file, None, &ripper, &cfg, &dedup, &subscr_tx) {
+                                journal.complete(file);
+                            }
                         });
 
                         // Tell the batcher we're done so that it can send the next round
@@ -318,6 +468,13 @@ impl<'io> Batcher<'io> {
         let mut is_last_batch = false;
 
         while !(self.state.complete || stop_flag::is_set()) {
+            stop_flag::wait_while_paused();
+
+            // The rip could've been cancelled while paused
+            if stop_flag::is_set() {
+                break;
+            }
+
             // If this is the last batch, set the state to complete
             // and send the last batch. When complete this loop terminates.
             self.state.complete = is_last_batch;
@@ -358,35 +515,59 @@ impl<'io> Batcher<'io> {
 
         // Store the read lines into the buffer.
         // The buffer has a batch_size capacity so
-        // it won't re-allocate
+        // it won't re-allocate.
+        //
+        // Files that were ripped by a previous attempt are skipped.
+        let journal = &self.journal;
+        let mut read: usize = 0;
+
         self.file
             .lines()
             .take(self.batch_size)
             .filter_map(|f| f.ok())
+            .inspect(|_| read += 1)
+            .filter(|line| !journal.is_completed(Path::new(line)))
             .for_each(|line| buffer.push(line));
 
         self.batch_number += 1;
 
         // Have a way of notifying the caller that this is is the last batch,
         // and should not be called again.
-        buffer.len() < self.batch_size
+        read < self.batch_size
     }
 }
 
-pub fn strict_loading(strict: bool) -> impl Fn(&Path) -> bool {
-    match strict {
-        true => move |path: &Path| {
-            const EXT: &[&str] = &[
-                "it", "xm", "s3m", "mod", "umx", "mptm", "IT", "XM", "S3M", "MOD", "UMX", "MPTM",
-            ];
-
-            let Some(ext) = path.extension().and_then(|f| f.to_str()) else {
-                return false;
-            };
+/// Combine strict loading with the user's custom filters.
+///
+/// The filter is called with a path and the folder it was found in (see [`parent`]).
+/// Fails if the custom filters are invalid.
+pub fn file_filter(
+    strict: bool,
+    filters: &FilterConfig,
+) -> Result<impl Fn(&Path, &Path) -> bool, String> {
+    let strict_loading = strict_loading(strict);
+    let filters = filters.build()?;
+
+    // Archives are exempt from strict loading, their members are checked instead.
+    Ok(move |path: &Path, root: &Path| {
+        (archive::is_archive(path) || strict_loading(path)) && filters.matches(path, root)
+    })
+}
 
-            EXT.contains(&ext)
-        },
+/// Files that were selected on their own are relative to their parent folder
+pub fn parent(file: &Path) -> &Path {
+    file.parent().unwrap_or(Path::new(""))
+}
 
+/// Only accept files that look like modules.
+///
+/// Files are identified by their contents, so misnamed modules are found and
+/// files with a misleading extension are skipped.
+/// Files with a module's extension are checked first, as only their signature has to be read.
+/// Compressed modules are accepted by name, since they have to be decompressed first.
+pub fn strict_loading(strict: bool) -> impl Fn(&Path) -> bool {
+    match strict {
+        true => |path: &Path| wrapper::is_wrapped(path) || sniff::is_module(path),
         false => |_: &Path| true,
     }
 }
@@ -0,0 +1,335 @@
+//! Rip modules stored in archives, without extracting them to the disk.
+//!
+//! Supported archives: ``.zip``, ``.7z``, ``.tar``, ``.tar.gz`` (``.tgz``) and ``.lha`` (``.lzh``)
+//!
+//! Every member is decoded in memory.
+//! The members are identified as ``archive/member``, e.g. ``music.zip/songs/intro.it``.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+
+use data::config::SampleRippingConfig;
+use xmodits_lib::Ripper;
+
+use super::dedup::Deduplicator;
+use super::error::RipError;
+use super::export::{self, folder_name, Report};
+use super::{sniff, wrapper};
+use crate::ripper::stop_flag;
+
+/// Members larger than this are skipped
+const MAX_MEMBER_SIZE: u64 = 128 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Zip,
+    SevenZip,
+    Tar,
+    TarGz,
+    Lha,
+}
+
+impl Kind {
+    fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+
+        let kind = match name.rsplit_once('.')?.1 {
+            "zip" => Self::Zip,
+            "7z" => Self::SevenZip,
+            "tar" => Self::Tar,
+            "tgz" => Self::TarGz,
+            "gz" if name.ends_with(".tar.gz") => Self::TarGz,
+            "lha" | "lzh" => Self::Lha,
+            _ => return None,
+        };
+
+        Some(kind)
+    }
+}
+
+/// Is the file an archive that can be ripped from?
+pub fn is_archive(path: &Path) -> bool {
+    Kind::detect(path).is_some()
+}
+
+/// Rip every module stored in an archive.
+///
+/// ``each`` is called at least once, with a failed report if the archive couldn't be read.
+pub fn extract(
+    archive: &Path,
+    ripper: &Ripper,
+    cfg: &SampleRippingConfig,
+    dedup: &Deduplicator,
+    mut each: impl FnMut(Report),
+) {
+    let strict = cfg.strict;
+
+    // Members have to be decoded before their contents can be checked,
+    // so skip the ones that aren't named like a module first.
+    let filter = |member: &Path| {
+        !is_archive(member)
+            && (!strict || wrapper::is_wrapped(member) || sniff::has_module_name(member))
+    };
+
+    let mut found = false;
+
+    let result = for_each_member(archive, &filter, &mut |member, data| {
+        stop_flag::wait_while_paused();
+
+        if stop_flag::is_set() {
+            return false;
+        }
+
+        let is_module =
+            |data: &[u8]| wrapper::is_wrapped(&member) || sniff::is_module_data(&member, data);
+
+        if strict && data.as_ref().is_ok_and(|data| !is_module(data)) {
+            return true;
+        }
+
+        found = true;
+
+        let report = match data {
+            Ok(data) => extract_member(archive, &member, &data, ripper, cfg, dedup),
+            Err(error) => export::failed(archive.join(&member), cfg, error),
+        };
+
+        each(report);
+        true
+    });
+
+    match result {
+        Err(error) => each(export::failed(archive.to_owned(), cfg, error)),
+        Ok(()) if !found && !stop_flag::is_set() => {
+            each(export::failed(archive.to_owned(), cfg, RipError::NoModules))
+        }
+        Ok(()) => (),
+    }
+}
+
+/// Rip a module that was decoded from an archive.
+///
+/// If ``self_contained`` is set, the samples are placed in ``archive/member``.
+fn extract_member(
+    archive: &Path,
+    member: &Path,
+    data: &[u8],
+    ripper: &Ripper,
+    cfg: &SampleRippingConfig,
+    dedup: &Deduplicator,
+) -> Report {
+    let source = archive.join(member);
+
+    let destination = match cfg.self_contained {
+        true => {
+            let mut folder = cfg.destination.join(folder_name(archive));
+
+            if let Some(parent) = member.parent() {
+                folder.push(parent);
+            }
+
+            folder.join(folder_name(member))
+        }
+        false => cfg.destination.to_owned(),
+    };
+
+    export::extract_with(source, destination, ripper, cfg, dedup, |path| {
+        wrapper::load_bytes(data, path)
+    })
+}
+
+/// Call ``each`` with the contents of every member that satisfies the filter.
+///
+/// Stops early if ``each`` returns false.
+fn for_each_member(
+    archive: &Path,
+    filter: &dyn Fn(&Path) -> bool,
+    each: &mut dyn FnMut(PathBuf, io::Result<Vec<u8>>) -> bool,
+) -> io::Result<()> {
+    let Some(kind) = Kind::detect(archive) else {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Unsupported archive",
+        ));
+    };
+
+    match kind {
+        Kind::Zip => zip(archive, filter, each),
+        Kind::SevenZip => seven_zip(archive, filter, each),
+        Kind::Tar => tar(BufReader::new(File::open(archive)?), filter, each),
+        Kind::TarGz => tar(
+            flate2::read::GzDecoder::new(BufReader::new(File::open(archive)?)),
+            filter,
+            each,
+        ),
+        Kind::Lha => lha(archive, filter, each),
+    }
+}
+
+fn zip(
+    archive: &Path,
+    filter: &dyn Fn(&Path) -> bool,
+    each: &mut dyn FnMut(PathBuf, io::Result<Vec<u8>>) -> bool,
+) -> io::Result<()> {
+    let mut zip = zip::ZipArchive::new(BufReader::new(File::open(archive)?))?;
+
+    for index in 0..zip.len() {
+        // Filter on the central directory, so members that aren't modules are never opened
+        let Some(name) = zip.name_for_index(index) else {
+            continue;
+        };
+
+        if name.ends_with('/') {
+            continue;
+        }
+
+        // Ignore members that could escape the archive, e.g. "../../song.it"
+        let Some(name) = enclosed(Path::new(name)).filter(|name| filter(name)) else {
+            continue;
+        };
+
+        // e.g. an encrypted member, or an unsupported compression method
+        let data = zip
+            .by_index(index)
+            .map_err(io::Error::from)
+            .and_then(|mut file| {
+                let size = file.size();
+                read(&mut file, size)
+            });
+
+        if !each(name, data) {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn seven_zip(
+    archive: &Path,
+    filter: &dyn Fn(&Path) -> bool,
+    each: &mut dyn FnMut(PathBuf, io::Result<Vec<u8>>) -> bool,
+) -> io::Result<()> {
+    let mut reader = sevenz_rust::SevenZReader::open(archive, sevenz_rust::Password::empty())
+        .map_err(io::Error::other)?;
+
+    reader
+        .for_each_entries(|entry, data| {
+            if entry.is_directory() {
+                return Ok(true);
+            }
+
+            let member = enclosed(Path::new(entry.name()))
+                .filter(|name| filter(name))
+                .map(|name| (name, read(data, entry.size())));
+
+            // Members can share a compressed stream, so it must be read to get to the next one.
+            io::copy(data, &mut io::sink())?;
+
+            Ok(match member {
+                Some((name, data)) => each(name, data),
+                None => true,
+            })
+        })
+        .map_err(io::Error::other)
+}
+
+fn tar(
+    reader: impl Read,
+    filter: &dyn Fn(&Path) -> bool,
+    each: &mut dyn FnMut(PathBuf, io::Result<Vec<u8>>) -> bool,
+) -> io::Result<()> {
+    let mut archive = tar::Archive::new(reader);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let Some(name) = entry.path().ok().and_then(|path| enclosed(&path)) else {
+            continue;
+        };
+
+        if filter(&name) {
+            let size = entry.size();
+
+            if !each(name, read(&mut entry, size)) {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn lha(
+    archive: &Path,
+    filter: &dyn Fn(&Path) -> bool,
+    each: &mut dyn FnMut(PathBuf, io::Result<Vec<u8>>) -> bool,
+) -> io::Result<()> {
+    let mut reader = delharc::parse_file(archive)?;
+
+    loop {
+        let header = reader.header();
+
+        if !header.is_directory() {
+            let size = header.original_size;
+
+            if let Some(name) = enclosed(&header.parse_pathname()).filter(|name| filter(name)) {
+                let data = match reader.is_decoder_supported() {
+                    true => read(&mut reader, size).and_then(|data| {
+                        reader.crc_check()?;
+                        Ok(data)
+                    }),
+                    false => Err(io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        "Unsupported LHA compression method",
+                    )),
+                };
+
+                if !each(name, data) {
+                    break;
+                }
+            }
+        }
+
+        if !reader.next_file()? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Read a member into memory
+fn read(reader: &mut dyn Read, size: u64) -> io::Result<Vec<u8>> {
+    if size > MAX_MEMBER_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Member is too large to be ripped from an archive",
+        ));
+    }
+
+    let mut data = Vec::with_capacity(size as usize);
+    reader.take(MAX_MEMBER_SIZE).read_to_end(&mut data)?;
+
+    Ok(data)
+}
+
+/// Only keep the normal components of a path, so a member can't point outside of the archive.
+fn enclosed(path: &Path) -> Option<PathBuf> {
+    let mut enclosed = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => enclosed.push(part),
+            Component::CurDir => (),
+            _ => return None,
+        }
+    }
+
+    (!enclosed.as_os_str().is_empty()).then_some(enclosed)
+}
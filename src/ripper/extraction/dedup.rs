@@ -0,0 +1,140 @@
+//! Detect identical samples across a rip.
+//!
+//! Samples are identified by hashing their PCM data, along with anything else that affects the
+//! exported file (sample rate, bit depth, channels and loop points).
+//!
+//! The index is shared between every worker.
+//! The first worker to claim a hash exports the sample, and the rest treat it as a duplicate
+//! once it's been written. If it couldn't be written, the next worker exports it instead.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use data::config::sample_ripping::Dedup;
+use data::config::SampleRippingConfig;
+use parking_lot::{Condvar, Mutex};
+use serde::Serialize;
+use xmodits_lib::Sample;
+
+/// Duplicates are recorded here when using [Dedup::Manifest]
+pub const MANIFEST: &str = "xmodits-duplicates.jsonl";
+
+pub type Hash = [u8; 32];
+
+#[derive(Debug)]
+pub struct Deduplicator {
+    mode: Dedup,
+    seen: Mutex<HashMap<Hash, State>>,
+    /// Notifies workers waiting on a sample that's being exported
+    exported: Condvar,
+    manifest_path: PathBuf,
+    manifest: Mutex<Option<BufWriter<File>>>,
+}
+
+#[derive(Debug)]
+enum State {
+    /// The first copy is being exported
+    Exporting,
+    /// Where the first copy was written
+    Exported(PathBuf),
+}
+
+#[derive(Serialize)]
+struct Entry<'a> {
+    source: &'a Path,
+    duplicate: &'a Path,
+    original: &'a Path,
+}
+
+impl Deduplicator {
+    pub fn new(cfg: &SampleRippingConfig) -> Self {
+        Self {
+            mode: cfg.dedup,
+            seen: Mutex::new(HashMap::new()),
+            exported: Condvar::new(),
+            manifest_path: cfg.destination.join(MANIFEST),
+            manifest: Mutex::new(None),
+        }
+    }
+
+    pub fn mode(&self) -> Dedup {
+        self.mode
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.mode != Dedup::Off
+    }
+
+    pub fn hash(smp: &Sample, pcm: &[u8]) -> Hash {
+        let mut hasher = blake3::Hasher::new();
+
+        hasher.update(&smp.rate.to_le_bytes());
+        hasher.update(&[smp.bits(), smp.is_signed() as u8, smp.is_stereo() as u8]);
+        hasher.update(format!("{:?}", smp.looping).as_bytes());
+        hasher.update(pcm);
+
+        *hasher.finalize().as_bytes()
+    }
+
+    /// Returns the path of the first copy if the sample was seen before.
+    ///
+    /// Otherwise, the caller must export the sample,
+    /// and then [settle](Self::settle) or [release](Self::release) the hash.
+    ///
+    /// If the first copy is still being exported, this waits until it's settled or released.
+    pub fn claim(&self, hash: Hash) -> Option<PathBuf> {
+        let mut seen = self.seen.lock();
+
+        loop {
+            match seen.get(&hash) {
+                Some(State::Exported(original)) => return Some(original.clone()),
+                Some(State::Exporting) => self.exported.wait(&mut seen),
+                None => {
+                    seen.insert(hash, State::Exporting);
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// The first copy was written to the given path, which may differ if it was renamed.
+    pub fn settle(&self, hash: Hash, path: &Path) {
+        self.seen
+            .lock()
+            .insert(hash, State::Exported(path.to_owned()));
+        self.exported.notify_all();
+    }
+
+    /// The first copy couldn't be exported, so the next claimant becomes the first copy.
+    pub fn release(&self, hash: Hash) {
+        self.seen.lock().remove(&hash);
+        self.exported.notify_all();
+    }
+
+    /// Record a duplicate in the manifest
+    pub fn record(&self, source: &Path, duplicate: &Path, original: &Path) -> io::Result<()> {
+        let mut manifest = self.manifest.lock();
+
+        let file = match manifest.as_mut() {
+            Some(file) => file,
+            None => manifest.insert(BufWriter::new(
+                File::options()
+                    .create(true)
+                    .append(true)
+                    .open(&self.manifest_path)?,
+            )),
+        };
+
+        let entry = Entry {
+            source,
+            duplicate,
+            original,
+        };
+
+        serde_json::to_writer(&mut *file, &entry)?;
+        file.write_all(b"\n")?;
+        file.flush()
+    }
+}
@@ -1,3 +1,4 @@
+use std::io;
 use std::path::PathBuf;
 
 use xmodits_lib::Error;
@@ -27,6 +28,52 @@ pub enum Reason {
     Multiple(Vec<(usize, String)>),
 }
 
+impl std::fmt::Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reason::Single(reason) => write!(f, "{reason}"),
+            Reason::Multiple(reasons) => {
+                for (raw_index, reason) in reasons {
+                    write!(f, "Sample {raw_index}: {reason}\n     ")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Why the samples couldn't be ripped from a module
+#[derive(Debug)]
+pub enum RipError {
+    NoSamples,
+    NoneSelected,
+    NoModules,
+    Xmodits(Error),
+}
+
+impl std::fmt::Display for RipError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RipError::NoSamples => write!(f, "Module doesn't have any samples"),
+            RipError::NoneSelected => write!(f, "Module doesn't have the selected samples"),
+            RipError::NoModules => write!(f, "Archive doesn't contain any modules"),
+            RipError::Xmodits(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl From<Error> for RipError {
+    fn from(error: Error) -> Self {
+        Self::Xmodits(error)
+    }
+}
+
+impl From<io::Error> for RipError {
+    fn from(error: io::Error) -> Self {
+        Self::Xmodits(error.into())
+    }
+}
+
 impl Failed {
     pub fn new(path: String, error: Error) -> Self {
         let path: PathBuf = path.into();
@@ -44,6 +91,16 @@ impl Failed {
         Self { path, reason }
     }
 
+    pub fn rip(path: String, error: RipError) -> Self {
+        match error {
+            RipError::Xmodits(error) => Self::new(path, error),
+            error => Self {
+                path: path.into(),
+                reason: Reason::Single(error.to_string()),
+            },
+        }
+    }
+
     pub fn filename(&self) -> &str {
         filename(&self.path)
     }
@@ -146,10 +146,9 @@ impl ErrorHandler {
                 let _ = file.write_all(reason.as_bytes()).await;
             }
             Reason::Multiple(reasons) => {
-                //todo include raw index
-                for (_raw_idx, reason) in reasons {
+                for (raw_idx, reason) in reasons {
+                    let reason = format!("Sample {raw_idx}: {reason}\n     ");
                     let _ = file.write_all(reason.as_bytes()).await;
-                    let _ = file.write_all(b"\n").await;
                 }
             }
         }
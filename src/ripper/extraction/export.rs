@@ -0,0 +1,672 @@
+//! Rip the samples from a single module
+
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use data::analysis;
+use data::config::sample_ripping::{Collision, Dedup, Noise};
+use data::config::{ProcessingConfig, SampleRippingConfig};
+use data::export::process;
+use data::export::sf2::SoundFont;
+use data::export::sfz::Instrument;
+use data::export::{ExportFormat, Tags};
+use xmodits_lib::export::dsp;
+use xmodits_lib::export::name::Context;
+use xmodits_lib::{AudioTrait, Error, Module, Ripper, Sample};
+
+use super::dedup::Deduplicator;
+use super::error::{Failed, Reason, RipError};
+use super::manifest::{self, Manifest, Status};
+use super::sanitize::sanitize;
+use super::wrapper;
+use crate::logger;
+use crate::utils::filename;
+
+/// Give up renaming a sample after this many attempts
+const MAX_RENAME_ATTEMPTS: usize = 10_000;
+
+/// What happened when a module was ripped.
+#[derive(Debug)]
+pub struct Report {
+    /// The module that was ripped
+    pub source: PathBuf,
+    /// Where the samples were placed
+    pub destination: PathBuf,
+    /// Samples that were written
+    pub exported: Vec<PathBuf>,
+    /// Samples that were given a different name because the file already exists
+    pub renamed: Vec<PathBuf>,
+    /// Samples that weren't written because the file already exists
+    pub skipped: Vec<PathBuf>,
+    /// How many sample names had characters replaced
+    pub sanitized: usize,
+    /// Samples that were already ripped, along with the path of the first copy
+    pub duplicates: Vec<(PathBuf, PathBuf)>,
+    /// Samples that are most likely static noise. They were either skipped or quarantined.
+    pub noise: Vec<PathBuf>,
+    pub elapsed: Duration,
+    pub failed: Option<Failed>,
+}
+
+impl Report {
+    pub fn is_ok(&self) -> bool {
+        self.failed.is_none()
+    }
+}
+
+/// Samples that were ripped from a module, as well as the ones that couldn't
+#[derive(Default)]
+struct Ripped {
+    exported: Vec<PathBuf>,
+    renamed: Vec<PathBuf>,
+    skipped: Vec<PathBuf>,
+    sanitized: usize,
+    duplicates: Vec<(PathBuf, PathBuf)>,
+    noise: Vec<PathBuf>,
+    errors: Vec<(usize, String)>,
+}
+
+/// How a sample was written
+enum Outcome {
+    Written(PathBuf),
+    Renamed(PathBuf),
+    Skipped,
+    /// The sample was already exported. If it was hard linked, ``linked`` is the path of the link.
+    Duplicate {
+        original: PathBuf,
+        linked: Option<PathBuf>,
+    },
+}
+
+/// Rip the samples from a module.
+///
+/// If ``self_contained`` is set in the configuration,
+/// the samples are placed in a folder named after the module.
+pub fn extract(
+    file: impl AsRef<Path>,
+    ripper: &Ripper,
+    cfg: &SampleRippingConfig,
+    dedup: &Deduplicator,
+) -> Report {
+    extract_from(file.as_ref(), None, ripper, cfg, dedup)
+}
+
+/// Only rip the samples whose internal index is in ``selected``.
+///
+/// The samples are named the same way as if every sample was ripped.
+pub fn extract_selected(
+    file: impl AsRef<Path>,
+    selected: &BTreeSet<usize>,
+    ripper: &Ripper,
+    cfg: &SampleRippingConfig,
+    dedup: &Deduplicator,
+) -> Report {
+    extract_from(file.as_ref(), Some(selected), ripper, cfg, dedup)
+}
+
+fn extract_from(
+    file: &Path,
+    selected: Option<&BTreeSet<usize>>,
+    ripper: &Ripper,
+    cfg: &SampleRippingConfig,
+    dedup: &Deduplicator,
+) -> Report {
+    let source = file.to_owned();
+
+    let destination = match cfg.self_contained {
+        true => cfg.destination.join(folder_name(&source)),
+        false => cfg.destination.to_owned(),
+    };
+
+    rip_with(source, destination, selected, ripper, cfg, dedup, |file| {
+        wrapper::load(&mut File::open(file)?, file)
+    })
+}
+
+/// Rip the samples from a module that's loaded with ``load``.
+///
+/// ``source`` doesn't have to exist, e.g. it could be a module stored in an archive.
+pub fn extract_with(
+    source: PathBuf,
+    destination: PathBuf,
+    ripper: &Ripper,
+    cfg: &SampleRippingConfig,
+    dedup: &Deduplicator,
+    load: impl Fn(&Path) -> Result<Module, Error>,
+) -> Report {
+    rip_with(source, destination, None, ripper, cfg, dedup, load)
+}
+
+fn rip_with(
+    source: PathBuf,
+    destination: PathBuf,
+    selected: Option<&BTreeSet<usize>>,
+    ripper: &Ripper,
+    cfg: &SampleRippingConfig,
+    dedup: &Deduplicator,
+    load: impl Fn(&Path) -> Result<Module, Error>,
+) -> Report {
+    let timer = Instant::now();
+
+    let result = logger::log_file_on_panic(&source, |file| {
+        let module = load(file)?;
+        rip_module(module, file, &destination, selected, ripper, cfg, dedup)
+    });
+
+    let (ripped, failed) = match result {
+        Ok(ripped) if ripped.errors.is_empty() => (ripped, None),
+        Ok(mut ripped) => {
+            let failed = Failed {
+                path: source.clone(),
+                reason: Reason::Multiple(std::mem::take(&mut ripped.errors)),
+            };
+            (ripped, Some(failed))
+        }
+        Err(error) => (
+            Ripped::default(),
+            Some(Failed::rip(source.display().to_string(), error)),
+        ),
+    };
+
+    Report {
+        source,
+        destination,
+        exported: ripped.exported,
+        renamed: ripped.renamed,
+        skipped: ripped.skipped,
+        sanitized: ripped.sanitized,
+        duplicates: ripped.duplicates,
+        noise: ripped.noise,
+        elapsed: timer.elapsed(),
+        failed,
+    }
+}
+
+/// A report for a file that couldn't be ripped at all
+pub fn failed(source: PathBuf, cfg: &SampleRippingConfig, error: impl Into<RipError>) -> Report {
+    Report {
+        failed: Some(Failed::rip(source.display().to_string(), error.into())),
+        source,
+        destination: cfg.destination.to_owned(),
+        exported: Vec::new(),
+        renamed: Vec::new(),
+        skipped: Vec::new(),
+        sanitized: 0,
+        duplicates: Vec::new(),
+        noise: Vec::new(),
+        elapsed: Duration::ZERO,
+    }
+}
+
+fn rip_module(
+    module: Module,
+    file: &Path,
+    destination: &Path,
+    selected: Option<&BTreeSet<usize>>,
+    ripper: &Ripper,
+    cfg: &SampleRippingConfig,
+    dedup: &Deduplicator,
+) -> Result<Ripped, RipError> {
+    let samples = module.samples();
+
+    if samples.is_empty() {
+        return Err(RipError::NoSamples);
+    }
+
+    let is_selected = |smp: &Sample| match selected {
+        Some(selected) => selected.contains(&(smp.index_raw() as usize)),
+        None => true,
+    };
+
+    if !samples.iter().any(is_selected) {
+        return Err(RipError::NoneSelected);
+    }
+
+    std::fs::create_dir_all(destination)?;
+
+    let context = Context {
+        total: samples.len(),
+        extension: ripper.format.extension(),
+        highest: samples
+            .iter()
+            .map(|smp| smp.index_raw() as usize)
+            .max()
+            .unwrap_or_default(),
+        source_path: Some(file),
+    };
+
+    let exporter = Exporter {
+        module: &module,
+        source: file,
+        ripper,
+        format: cfg.exported_format,
+        embed_tags: cfg.embed_tags,
+        processing: &cfg.processing,
+        noise: cfg.noise,
+        collision: cfg.collision,
+        dedup,
+    };
+
+    let mut ripped = Ripped {
+        exported: Vec::with_capacity(samples.len()),
+        ..Default::default()
+    };
+
+    let mut instrument = cfg.sfz.then(Instrument::default);
+    let mut soundfont = cfg.soundfont.then(|| SoundFont::new(&module.info().name));
+    let mut manifest = cfg.manifest.then(|| Manifest::new(&module, file));
+
+    for (index, smp) in samples.iter().enumerate() {
+        // Keep the index of samples that weren't selected, so that names don't change
+        if !is_selected(smp) {
+            continue;
+        }
+
+        let mut name = (ripper.namer_func)(smp, &context, index);
+
+        if cfg.sanitize {
+            let sanitized = sanitize(&name);
+
+            if sanitized != name {
+                ripped.sanitized += 1;
+                name = sanitized;
+            }
+        }
+
+        let (smp, pcm, noise) = match exporter.load(smp) {
+            Ok(loaded) => loaded,
+            Err(error) => {
+                if let Some(manifest) = &mut manifest {
+                    manifest.add(index, smp, None, destination, Status::Failed);
+                }
+                ripped
+                    .errors
+                    .push((smp.index_raw() as usize, error.to_string()));
+                continue;
+            }
+        };
+
+        if noise && cfg.noise == Noise::Skip {
+            if let Some(manifest) = &mut manifest {
+                manifest.add(index, &smp, None, destination, Status::Noise);
+            }
+            ripped.noise.push(destination.join(name));
+            continue;
+        }
+
+        let path = match noise {
+            true => destination.join(Noise::FOLDER).join(name),
+            false => destination.join(name),
+        };
+
+        // Keep static noise out of instruments
+        if let Some(soundfont) = soundfont.as_mut().filter(|_| !noise) {
+            soundfont.add(&smp, pcm.clone());
+        }
+
+        let outcome = exporter.export(&smp, pcm, &path);
+
+        if let (Some(instrument), Ok(outcome), false) = (&mut instrument, &outcome, noise) {
+            instrument.add(&smp, outcome.file(&path), destination);
+        }
+
+        if let Some(manifest) = &mut manifest {
+            match &outcome {
+                Ok(outcome) => {
+                    let file = outcome.file(&path);
+                    let status = match noise {
+                        true => Status::Noise,
+                        false => outcome.status(),
+                    };
+                    manifest.add(index, &smp, Some(file), destination, status);
+                }
+                Err(_) => manifest.add(index, &smp, None, destination, Status::Failed),
+            }
+        }
+
+        if let (true, Ok(outcome)) = (noise, &outcome) {
+            ripped.noise.push(outcome.file(&path).to_owned());
+        }
+
+        match outcome {
+            Ok(Outcome::Written(path)) => ripped.exported.push(path),
+            Ok(Outcome::Renamed(path)) => {
+                ripped.renamed.push(path.clone());
+                ripped.exported.push(path);
+            }
+            Ok(Outcome::Skipped) => ripped.skipped.push(path),
+            Ok(Outcome::Duplicate { original, linked }) => {
+                if let Some(linked) = linked {
+                    ripped.exported.push(linked);
+                }
+                ripped.duplicates.push((path, original));
+            }
+            Err(error) => ripped
+                .errors
+                .push((smp.index_raw() as usize, error.to_string())),
+        }
+    }
+
+    if let Some(instrument) = instrument.filter(|instrument| !instrument.is_empty()) {
+        let path = destination.join(format!("{}.sfz", folder_name(file)));
+        instrument.save(&path, file)?;
+    }
+
+    if let Some(soundfont) = soundfont.filter(|soundfont| !soundfont.is_empty()) {
+        let path = destination.join(format!("{}.sf2", folder_name(file)));
+        soundfont.save(&path)?;
+    }
+
+    if let Some(manifest) = manifest {
+        let path = match cfg.self_contained {
+            true => destination.join(manifest::NAME),
+            false => destination.join(format!("{}_{}", folder_name(file), manifest::NAME)),
+        };
+        manifest.save(&path)?;
+    }
+
+    Ok(ripped)
+}
+
+impl Outcome {
+    /// The file that holds the sample. ``path`` is where it was meant to be written.
+    fn file<'a>(&'a self, path: &'a Path) -> &'a Path {
+        match self {
+            Outcome::Written(path) | Outcome::Renamed(path) => path,
+            Outcome::Skipped => path,
+            Outcome::Duplicate { original, linked } => linked.as_ref().unwrap_or(original),
+        }
+    }
+
+    fn status(&self) -> Status {
+        match self {
+            Outcome::Written(_) => Status::Exported,
+            Outcome::Renamed(_) => Status::Renamed,
+            Outcome::Skipped => Status::Skipped,
+            Outcome::Duplicate { .. } => Status::Duplicate,
+        }
+    }
+}
+
+/// A processed sample, its pcm data, and whether it's most likely static noise
+type Loaded<'s, 'a> = (Cow<'s, Sample>, Cow<'a, [u8]>, bool);
+
+/// Export samples from a module
+struct Exporter<'a> {
+    module: &'a Module,
+    source: &'a Path,
+    ripper: &'a Ripper,
+    format: ExportFormat,
+    embed_tags: bool,
+    processing: &'a ProcessingConfig,
+    noise: Noise,
+    collision: Collision,
+    dedup: &'a Deduplicator,
+}
+
+impl<'a> Exporter<'a> {
+    /// Returns the sample and its pcm data after it's been processed,
+    /// and whether it's most likely static noise.
+    ///
+    /// Samples are only checked for noise if it's enabled in the configuration.
+    fn load<'s>(&self, smp: &'s Sample) -> Result<Loaded<'s, 'a>, Error> {
+        let pcm = self.module.pcm(smp)?;
+
+        let noise = match self.noise {
+            Noise::Keep => false,
+            _ => is_noise(smp, &pcm),
+        };
+
+        if !self.processing.is_enabled() {
+            return Ok((Cow::Borrowed(smp), pcm, noise));
+        }
+
+        let (smp, pcm) = process::apply(self.processing, smp, pcm);
+        Ok((Cow::Owned(smp), Cow::Owned(pcm), noise))
+    }
+
+    fn export(&self, smp: &Sample, pcm: Cow<[u8]>, path: &Path) -> Result<Outcome, Error> {
+        // Templates can place samples in subfolders
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        if !self.dedup.is_enabled() {
+            return self.write(smp, pcm, path);
+        }
+
+        let hash = Deduplicator::hash(smp, &pcm);
+
+        if let Some(original) = self.dedup.claim(hash) {
+            if let Some(outcome) = self.duplicate(path, original)? {
+                return Ok(outcome);
+            }
+
+            // The first copy isn't available anymore, so export it anyway.
+            return self.write(smp, pcm, path);
+        }
+
+        let outcome = self.write(smp, pcm, path);
+
+        match &outcome {
+            Ok(Outcome::Written(path) | Outcome::Renamed(path)) => self.dedup.settle(hash, path),
+            _ => self.dedup.release(hash),
+        }
+
+        outcome
+    }
+
+    fn write(&self, smp: &Sample, pcm: Cow<[u8]>, path: &Path) -> Result<Outcome, Error> {
+        let Some((file, outcome)) = place(path, self.collision, create_new, File::create)? else {
+            return Ok(Outcome::Skipped);
+        };
+
+        let info = self.module.info();
+        let tags = self.embed_tags.then(|| Tags {
+            name: &smp.name,
+            filename: smp.filename.as_deref(),
+            module: &info.name,
+            source: self.source,
+            format: &info.format,
+            index: smp.index_raw() as usize,
+        });
+
+        write_sample(file, &*self.ripper.format, self.format, smp, pcm, tags)?;
+
+        Ok(outcome)
+    }
+
+    /// Returns ``None`` if the duplicate should be exported as normal.
+    fn duplicate(&self, path: &Path, original: PathBuf) -> Result<Option<Outcome>, Error> {
+        let linked = match self.dedup.mode() {
+            Dedup::Off => return Ok(None),
+            Dedup::Skip => None,
+            Dedup::Manifest => {
+                self.dedup.record(self.source, path, &original)?;
+                None
+            }
+            Dedup::HardLink => {
+                let link = |path: &Path| std::fs::hard_link(&original, path);
+
+                match place(path, self.collision, link, link) {
+                    Ok(Some((_, Outcome::Written(path) | Outcome::Renamed(path)))) => Some(path),
+                    Ok(_) => return Ok(Some(Outcome::Skipped)),
+                    Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+                    Err(error) => return Err(error.into()),
+                }
+            }
+        };
+
+        Ok(Some(Outcome::Duplicate { original, linked }))
+    }
+}
+
+/// Where a sample that was loaded on its own came from
+pub struct Origin<'a> {
+    /// Title of the module
+    pub module: &'a str,
+    /// Tracker format of the module
+    pub format: &'a str,
+    pub source: &'a Path,
+}
+
+/// Export a sample that has already been loaded, e.g. by the sample player.
+///
+/// The sample is processed and tagged the same way as when a module is ripped.
+///
+/// Returns where the sample was written, or ``None`` if it was skipped.
+pub fn export_loaded(
+    smp: &Sample,
+    pcm: Cow<[u8]>,
+    path: &Path,
+    origin: &Origin,
+    cfg: &SampleRippingConfig,
+    collision: Collision,
+) -> Result<Option<PathBuf>, Error> {
+    let (smp, pcm) = match cfg.processing.is_enabled() {
+        true => {
+            let (smp, pcm) = process::apply(&cfg.processing, smp, pcm);
+            (Cow::Owned(smp), Cow::Owned(pcm))
+        }
+        false => (Cow::Borrowed(smp), pcm),
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let Some((file, outcome)) = place(path, collision, create_new, File::create)? else {
+        return Ok(None);
+    };
+
+    let tags = cfg.embed_tags.then(|| Tags {
+        name: &smp.name,
+        filename: smp.filename.as_deref(),
+        module: origin.module,
+        source: origin.source,
+        format: origin.format,
+        index: smp.index_raw() as usize,
+    });
+
+    let format = cfg.exported_format;
+    write_sample(file, &*format.get_impl(), format, &smp, pcm, tags)?;
+
+    Ok(Some(outcome.file(path).to_owned()))
+}
+
+/// Write a sample to a file, and embed its tags if there are any.
+fn write_sample(
+    file: File,
+    writer: &dyn AudioTrait,
+    format: ExportFormat,
+    smp: &Sample,
+    pcm: Cow<[u8]>,
+    tags: Option<Tags>,
+) -> Result<(), Error> {
+    let mut file = BufWriter::new(file);
+
+    match tags {
+        Some(tags) => {
+            let mut buf = Vec::new();
+            writer.write(smp, pcm, &mut buf)?;
+            tags.embed(format, &mut buf)?;
+            file.write_all(&buf)?;
+        }
+        None => writer.write(smp, pcm, &mut file)?,
+    }
+
+    file.flush()?;
+
+    Ok(())
+}
+
+fn is_noise(smp: &Sample, pcm: &[u8]) -> bool {
+    let buffer = dsp::SampleBuffer::from(dsp::RawSample::new(smp, Cow::Borrowed(pcm)));
+    analysis::is_noise(&buffer.buf)
+}
+
+/// Create a file (or link) while following the collision policy.
+///
+/// ``create`` must fail if the file already exists.
+/// ``replace`` is used to overwrite it, and shouldn't fail if the file exists.
+///
+/// Returns ``None`` if it was skipped.
+fn place<T>(
+    path: &Path,
+    collision: Collision,
+    create: impl Fn(&Path) -> io::Result<T>,
+    replace: impl Fn(&Path) -> io::Result<T>,
+) -> io::Result<Option<(T, Outcome)>> {
+    let written = |created: T| Ok(Some((created, Outcome::Written(path.to_owned()))));
+
+    match create(path) {
+        Err(error) if error.kind() == ErrorKind::AlreadyExists => match collision {
+            // Unlink the file instead of truncating it, as it could be linked to another sample.
+            // Another worker could be writing a sample with the same name,
+            // so the file could already be gone, or be back again.
+            Collision::Overwrite => {
+                match std::fs::remove_file(path) {
+                    Err(error) if error.kind() != ErrorKind::NotFound => return Err(error),
+                    _ => (),
+                }
+                written(replace(path)?)
+            }
+            Collision::Skip => Ok(None),
+            Collision::Error => Err(io::Error::new(
+                ErrorKind::AlreadyExists,
+                format!("'{}' already exists", path.display()),
+            )),
+            Collision::Rename => {
+                let (created, path) = create_renamed(path, create)?;
+                Ok(Some((created, Outcome::Renamed(path))))
+            }
+        },
+        created => written(created?),
+    }
+}
+
+/// Fails if the file already exists.
+///
+/// Other threads could be writing to the same folder,
+/// so checking if the file exists beforehand isn't enough.
+fn create_new(path: &Path) -> io::Result<File> {
+    OpenOptions::new().write(true).create_new(true).open(path)
+}
+
+/// Create a file with a number appended to its name, e.g. "kick_1.wav"
+fn create_renamed<T>(
+    path: &Path,
+    create: impl Fn(&Path) -> io::Result<T>,
+) -> io::Result<(T, PathBuf)> {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path.extension().map(|ext| ext.to_string_lossy());
+
+    for n in 1..=MAX_RENAME_ATTEMPTS {
+        let name = match &extension {
+            Some(extension) => format!("{stem}_{n}.{extension}"),
+            None => format!("{stem}_{n}"),
+        };
+
+        let path = path.with_file_name(name);
+
+        match create(&path) {
+            Ok(created) => return Ok((created, path)),
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error),
+        }
+    }
+
+    Err(io::Error::new(
+        ErrorKind::AlreadyExists,
+        format!("Could not find a free name for '{}'", path.display()),
+    ))
+}
+
+/// Self contained folders are named after the module's filename, with '.' replaced with '_'
+pub fn folder_name(path: &Path) -> String {
+    filename(path).replace('.', "_")
+}
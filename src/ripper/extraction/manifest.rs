@@ -0,0 +1,154 @@
+//! An index of every sample ripped from a module, saved as JSON next to the samples.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use serde::Serialize;
+use xmodits_lib::{Info, Module, Sample};
+
+use super::wrapper;
+
+/// Name of the manifest when the module has its own folder
+pub const NAME: &str = "manifest.json";
+
+/// The same information that's shown when a module is selected.
+///
+/// It's only taken from ``module`` if the library can't load ``source`` by itself,
+/// e.g. the module is compressed or stored in an archive.
+pub fn info(module: &Module, source: &Path) -> Info {
+    if !wrapper::is_wrapped(source) {
+        if let Ok(info) = Info::new(source) {
+            return info;
+        }
+    }
+
+    let bytes: usize = module.samples().iter().map(|smp| smp.length as usize).sum();
+
+    Info {
+        name: module.info().name.to_owned(),
+        format: module.info().format.to_owned(),
+        total_samples: module.len(),
+        total_sample_size: bytes / 1024,
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct Manifest {
+    pub module: ModuleEntry,
+    pub samples: Vec<SampleEntry>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ModuleEntry {
+    pub title: String,
+    pub format: String,
+    pub source: String,
+    pub total_samples: usize,
+    /// In KiB
+    pub total_sample_size: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SampleEntry {
+    pub index_raw: usize,
+    /// Position of the sample in the module, starting from 0
+    pub index: usize,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    pub rate: u32,
+    pub bits: u8,
+    pub signed: bool,
+    pub stereo: bool,
+    /// In bytes
+    pub length: usize,
+    #[serde(rename = "loop")]
+    pub looping: LoopEntry,
+    /// Where the sample can be found, relative to the manifest if possible.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    pub status: Status,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LoopEntry {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Exported,
+    /// Given a different name because the file already exists
+    Renamed,
+    /// Not written because the file already exists
+    Skipped,
+    /// The sample was already ripped, ``output`` is the first copy (or a link to it)
+    Duplicate,
+    /// Most likely static noise. ``output`` is only set if the sample was quarantined.
+    Noise,
+    Failed,
+}
+
+impl Manifest {
+    pub fn new(module: &Module, source: &Path) -> Self {
+        let info = info(module, source);
+
+        Self {
+            module: ModuleEntry {
+                title: info.name.trim().to_owned(),
+                format: info.format,
+                source: source.display().to_string(),
+                total_samples: info.total_samples,
+                total_sample_size: info.total_sample_size,
+            },
+            samples: Vec::with_capacity(info.total_samples),
+        }
+    }
+
+    /// ``output`` is the file the sample was exported to, ``folder`` is where the manifest is saved.
+    pub fn add(
+        &mut self,
+        index: usize,
+        smp: &Sample,
+        output: Option<&Path>,
+        folder: &Path,
+        status: Status,
+    ) {
+        let output = output.map(|path| {
+            path.strip_prefix(folder)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        });
+
+        self.samples.push(SampleEntry {
+            index_raw: smp.index_raw() as usize,
+            index,
+            name: smp.name.trim().to_owned(),
+            filename: smp.filename.as_deref().map(|name| name.trim().to_owned()),
+            rate: smp.rate,
+            bits: smp.bits(),
+            signed: smp.is_signed(),
+            stereo: smp.is_stereo(),
+            length: smp.length as usize,
+            looping: LoopEntry {
+                kind: format!("{:?}", smp.looping.kind()).to_lowercase(),
+                start: smp.looping.start(),
+                end: smp.looping.end(),
+            },
+            output,
+            status,
+        });
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.flush()
+    }
+}
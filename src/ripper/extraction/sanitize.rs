@@ -0,0 +1,43 @@
+//! Make sure exported samples can be stored on Windows, FAT and exFAT.
+
+/// Characters that can't be used in a file name
+const RESERVED: &[char] = &['<', '>', ':', '"', '\\', '|', '?', '*'];
+
+/// Names that Windows reserves for devices, with or without an extension.
+const DEVICES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Sanitize a relative path. Each folder (separated by '/') is sanitized on its own.
+pub fn sanitize(name: &str) -> String {
+    name.split('/')
+        .map(sanitize_component)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn sanitize_component(name: &str) -> String {
+    let mut name: String = name
+        .chars()
+        .map(|c| match c {
+            c if RESERVED.contains(&c) || c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    // Windows ignores trailing dots and spaces
+    name.truncate(name.trim_end_matches(['.', ' ']).len());
+
+    let stem = name.split('.').next().unwrap_or_default();
+
+    if DEVICES
+        .iter()
+        .any(|device| device.eq_ignore_ascii_case(stem.trim()))
+    {
+        name.insert(0, '_');
+    }
+
+    name
+}
@@ -0,0 +1,199 @@
+//! Identify modules by their contents instead of their file extension.
+//!
+//! Only the start of a file is read, which is enough to find the signature of every format.
+//! Files with a module's extension have their signature read first,
+//! and common files that are never modules aren't read at all.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Protracker stores its signature at offset 1080, so this should cover every format.
+const SNIFF_SIZE: u64 = 2048;
+
+/// Extensions of supported modules
+pub const EXTENSIONS: &[&str] = &["it", "xm", "s3m", "mod", "umx", "mptm"];
+
+/// Extensions of files that are often found next to modules, but are never modules.
+const OTHER_EXTENSIONS: &[&str] = &[
+    "txt", "nfo", "diz", "doc", "md", "htm", "html", "pdf", "png", "jpg", "jpeg", "gif", "bmp",
+    "ico", "wav", "mp3", "ogg", "flac", "aiff", "mid", "midi", "exe", "dll", "sfv", "md5",
+];
+
+/// Signatures stored at offset 1080 of a Protracker module (and its clones).
+const MOD_TAGS: &[&[u8; 4]] = &[
+    b"M.K.", b"M!K!", b"M&K!", b"N.T.", b"FLT4", b"FLT8", b"EXO4", b"EXO8", b"CD81", b"OKTA",
+    b"OCTA",
+];
+
+/// Unreal package signature (0x9E2A83C1, little endian)
+const UMX_MAGIC: &[u8] = &[0xC1, 0x83, 0x2A, 0x9E];
+
+/// Original 15 sample modules don't have a signature,
+/// but they're at least as large as their header.
+const MIN_UNTAGGED_MOD_SIZE: usize = 600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Impulse Tracker, also used by ``.mptm``
+    It,
+    Xm,
+    S3m,
+    Mod,
+    Umx,
+}
+
+impl Format {
+    /// The format that uses this extension
+    fn from_extension(ext: &str) -> Option<Self> {
+        Some(match ext.to_ascii_lowercase().as_str() {
+            "it" | "mptm" => Self::It,
+            "xm" => Self::Xm,
+            "s3m" => Self::S3m,
+            "mod" => Self::Mod,
+            "umx" => Self::Umx,
+            _ => return None,
+        })
+    }
+
+    /// How much of a file has to be read to find the signature
+    fn signature_size(self) -> u64 {
+        match self {
+            Self::It | Self::Umx => 4,
+            Self::Xm => 17,
+            Self::S3m => 48,
+            Self::Mod => 1084,
+        }
+    }
+}
+
+/// Identify a module from the start of its contents.
+pub fn identify(header: &[u8]) -> Option<Format> {
+    let at = |offset: usize, magic: &[u8]| {
+        header
+            .get(offset..offset + magic.len())
+            .is_some_and(|bytes| bytes == magic)
+    };
+
+    if at(0, b"IMPM") {
+        return Some(Format::It);
+    }
+
+    // Some trackers don't capitalize "Module"
+    if header
+        .get(..17)
+        .is_some_and(|bytes| bytes.eq_ignore_ascii_case(b"Extended Module: "))
+    {
+        return Some(Format::Xm);
+    }
+
+    if at(44, b"SCRM") {
+        return Some(Format::S3m);
+    }
+
+    if at(0, UMX_MAGIC) {
+        return Some(Format::Umx);
+    }
+
+    let tag = header.get(1080..1084)?;
+
+    if MOD_TAGS.iter().any(|magic| tag == *magic) || is_channel_tag(tag) {
+        return Some(Format::Mod);
+    }
+
+    None
+}
+
+/// Tags like "6CHN", "12CH", "TDZ4", or "8CHN"
+fn is_channel_tag(tag: &[u8]) -> bool {
+    match tag {
+        [n, b'C', b'H', b'N'] => n.is_ascii_digit(),
+        [a, b, b'C', b'H'] => a.is_ascii_digit() && b.is_ascii_digit(),
+        [b'T', b'D', b'Z', n] => n.is_ascii_digit(),
+        _ => false,
+    }
+}
+
+/// Does the file name look like a module? The extension isn't case sensitive.
+///
+/// Amiga style names, where the format is a prefix (e.g. ``mod.intro``) are also accepted.
+pub fn has_module_name(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+
+    let is_module_ext = |ext: &str| EXTENSIONS.iter().any(|e| e.eq_ignore_ascii_case(ext));
+
+    let suffix = name
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| is_module_ext(ext));
+    let prefix = name
+        .split_once('.')
+        .is_some_and(|(ext, _)| is_module_ext(ext));
+
+    suffix || prefix
+}
+
+/// Is the name of a Protracker module? e.g. ``song.mod`` or ``mod.song``
+fn has_mod_name(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+
+    let is_mod = |ext: &str| ext.eq_ignore_ascii_case("mod");
+
+    name.rsplit_once('.').is_some_and(|(_, ext)| is_mod(ext))
+        || name.split_once('.').is_some_and(|(ext, _)| is_mod(ext))
+}
+
+/// Does the data look like a module?
+///
+/// The name is only used for formats without a signature (15 sample Protracker modules).
+pub fn is_module_data(name: &Path, data: &[u8]) -> bool {
+    identify(data).is_some() || (has_mod_name(name) && data.len() >= MIN_UNTAGGED_MOD_SIZE)
+}
+
+/// Read the start of a file and check if it's a module.
+///
+/// Files with a module's extension only have their signature checked at first.
+/// If it doesn't match, the whole header is read in case the extension is wrong.
+/// The whole header is also read for other files (e.g. ``mod.intro`` or ``song.bak``),
+/// unless they're known to never be modules.
+pub fn is_module(path: &Path) -> bool {
+    let extension = path.extension().and_then(|ext| ext.to_str());
+
+    let format = match extension.map(|ext| (ext, Format::from_extension(ext))) {
+        Some((_, Some(format))) => Some(format),
+        Some((ext, None)) if is_other_extension(ext) && !has_module_name(path) => return false,
+        _ => None,
+    };
+
+    let Ok(mut file) = File::open(path) else {
+        return false;
+    };
+
+    let size = format.map_or(SNIFF_SIZE, Format::signature_size);
+    let mut header = Vec::with_capacity(SNIFF_SIZE as usize);
+
+    if file.by_ref().take(size).read_to_end(&mut header).is_err() {
+        return false;
+    }
+
+    if let Some(format) = format {
+        if identify(&header) == Some(format) {
+            return true;
+        }
+
+        let rest = SNIFF_SIZE - header.len() as u64;
+
+        if file.take(rest).read_to_end(&mut header).is_err() {
+            return false;
+        }
+    }
+
+    is_module_data(path, &header)
+}
+
+fn is_other_extension(ext: &str) -> bool {
+    OTHER_EXTENSIONS.iter().any(|e| e.eq_ignore_ascii_case(ext))
+}
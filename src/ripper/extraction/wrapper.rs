@@ -0,0 +1,94 @@
+//! Modules that were compressed on their own, e.g. ``song.mdz`` or ``song.mod.gz``.
+//!
+//! ``.mdz``, ``.s3z``, ``.itz`` and ``.xmz`` files can be zipped or gzipped.
+//! The wrapper is detected from its contents, so mislabelled files are also loaded.
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, Cursor, Read};
+use std::path::Path;
+
+use xmodits_lib::{Error, Module};
+
+use super::{archive, sniff};
+
+/// Extensions of compressed modules
+pub const EXTENSIONS: &[&str] = &["mdz", "s3z", "itz", "xmz"];
+
+/// Refuse to decompress modules larger than this
+const MAX_SIZE: u64 = 128 * 1024 * 1024;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// Is the file a compressed module?
+///
+/// A ``.gz`` file is only treated as one if the name underneath is a module, e.g. ``song.it.gz``
+pub fn is_wrapped(path: &Path) -> bool {
+    let Some(ext) = path.extension().and_then(|ext| ext.to_str()) else {
+        return false;
+    };
+
+    match ext.to_ascii_lowercase().as_str() {
+        "gz" => !archive::is_archive(path) && sniff::has_module_name(&path.with_extension("")),
+        ext => EXTENSIONS.contains(&ext),
+    }
+}
+
+/// Load a module, decompressing it first if it's wrapped.
+pub fn load(file: &mut File, path: &Path) -> Result<Module, Error> {
+    if !is_wrapped(path) {
+        return xmodits_lib::load(file, Some(path.to_owned()));
+    }
+
+    let mut data = Vec::new();
+    file.take(MAX_SIZE).read_to_end(&mut data)?;
+
+    load_bytes(&data, path)
+}
+
+/// Load a module that's already in memory, decompressing it first if it's wrapped.
+pub fn load_bytes(data: &[u8], path: &Path) -> Result<Module, Error> {
+    let data = match is_wrapped(path) {
+        true => unwrap(data)?,
+        false => Cow::Borrowed(data),
+    };
+
+    xmodits_lib::load(&mut Cursor::new(data.as_ref()), Some(path.to_owned()))
+}
+
+/// Decompress a wrapped module.
+///
+/// If it isn't compressed, it's returned as is.
+fn unwrap(data: &[u8]) -> io::Result<Cow<[u8]>> {
+    let mut module = Vec::new();
+
+    if data.starts_with(GZIP_MAGIC) {
+        flate2::read::GzDecoder::new(data)
+            .take(MAX_SIZE)
+            .read_to_end(&mut module)?;
+
+        return Ok(Cow::Owned(module));
+    }
+
+    if data.starts_with(ZIP_MAGIC) {
+        let mut zip = zip::ZipArchive::new(Cursor::new(data))?;
+
+        // The module should be the only file in the zip
+        for index in 0..zip.len() {
+            let file = zip.by_index(index)?;
+
+            if !file.is_dir() {
+                file.take(MAX_SIZE).read_to_end(&mut module)?;
+                return Ok(Cow::Owned(module));
+            }
+        }
+
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Compressed module is empty",
+        ));
+    }
+
+    Ok(Cow::Borrowed(data))
+}
@@ -45,4 +45,14 @@ impl Handle {
     pub fn cancel(&self) {
         stop_flag::set_cancel()
     }
+
+    /// Pause the ripping process. Workers will finish their current module first.
+    pub fn pause(&self) {
+        stop_flag::set_pause()
+    }
+
+    /// Continue a paused ripping process
+    pub fn resume(&self) {
+        stop_flag::clear_pause()
+    }
 }
@@ -0,0 +1,116 @@
+//! Add finished rips to the history
+//!
+//! The GUI and headless mode can finish a rip at the same time,
+//! so the history is locked while it's changed.
+
+use std::path::PathBuf;
+
+use data::history::{History, Outcome, Record};
+use data::Time;
+
+use super::extraction::error_handler::{self, ErrorHandler};
+use super::extraction::Failed;
+use super::journal::lock::Lock;
+use super::subscription::CompleteState;
+use super::{Job, Signal};
+
+impl From<&CompleteState> for Outcome {
+    fn from(state: &CompleteState) -> Self {
+        match state {
+            CompleteState::NoErrors => Self::NoErrors,
+            CompleteState::Cancelled => Self::Cancelled,
+            CompleteState::Aborted => Self::Aborted,
+            CompleteState::SomeErrors(errors) => Self::Errors {
+                total: errors.len() as u64,
+            },
+            CompleteState::TooMuchErrors { total, .. } => Self::Errors { total: *total },
+            CompleteState::TooMuchErrorsNoLog {
+                errors, discarded, ..
+            } => Self::Errors {
+                total: errors.len() as u64 + discarded,
+            },
+        }
+    }
+}
+
+/// Add a finished job to the history.
+///
+/// Errors that were only kept in memory are saved to the history folder, so they can be viewed
+/// later on.
+pub async fn record(job: Job, state: &CompleteState, time: &Time) {
+    let error_log = match state {
+        CompleteState::TooMuchErrors { log, .. } => Some(log.clone()),
+        state => match state.errors_ref() {
+            Some(errors) => save_errors(errors.clone()).await,
+            None => None,
+        },
+    };
+
+    let record = Record::new(
+        job.entries,
+        job.ripping,
+        job.naming,
+        job.filters,
+        job.samples,
+        Outcome::from(state),
+        time,
+        error_log,
+    );
+
+    let _ = tokio::task::spawn_blocking(move || add(record)).await;
+}
+
+/// Add a record to the history
+pub fn add(record: Record) {
+    if let Err(error) = modify(|history| history.push(record)) {
+        tracing::warn!("Could not save the rip history: {error}");
+    }
+}
+
+/// Name of the lock held while the history is changed
+const LOCK: &str = "history.lock";
+
+/// Change the latest saved history, and return it.
+///
+/// This blocks until nobody else is changing the history.
+pub fn modify(change: impl FnOnce(&mut History)) -> anyhow::Result<History> {
+    std::fs::create_dir_all(History::dir())?;
+    let _lock = Lock::acquire(&History::dir().join(LOCK))?;
+
+    let mut history = History::load();
+    change(&mut history);
+    history.save()?;
+
+    Ok(history)
+}
+
+async fn save_errors(errors: Vec<Failed>) -> Option<PathBuf> {
+    if let Err(error) = tokio::fs::create_dir_all(History::dir()).await {
+        tracing::warn!("Could not create the history folder: {error}");
+        return None;
+    }
+
+    let path = History::dir().join(error_handler::random_name());
+
+    match ErrorHandler::dump(errors, path).await {
+        Ok(path) => Some(path),
+        Err(error) => {
+            tracing::warn!("Could not save errors to the history: {error}");
+            None
+        }
+    }
+}
+
+impl From<Record> for Signal {
+    fn from(record: Record) -> Self {
+        Self {
+            samples: record.samples,
+            ..Self::new(
+                record.entries,
+                record.ripping,
+                record.naming,
+                record.filters,
+            )
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! Keep track of a rip so that it can be resumed if it's cancelled or XMODITS crashes.
+//!
+//! Every job is stored in its own folder in the config directory,
+//! so the GUI and headless mode can rip at the same time:
+//!
+//! * ``job.json`` - What was selected, and the configuration used.
+//! * ``traversal.txt`` - The files found when traversing the selected folders.
+//! * ``completed.txt`` - The files that have been ripped without any errors.
+//!   Files that failed are ripped again when the job is resumed.
+//! * ``lock`` - Held while the job is being ripped.
+//!
+//! The job is removed once the rip has finished. Interrupted jobs are kept until there are
+//! more than ``MAX_INTERRUPTED``, and the last one is resumed first.
+
+pub mod lock;
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use data::config::{self, FilterConfig, SampleNameConfig, SampleRippingConfig};
+use data::history::Selection;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use self::lock::Lock;
+use super::Signal;
+
+const JOBS_DIR: &str = "jobs";
+const JOB: &str = "job.json";
+const LOCK: &str = "lock";
+const TRAVERSAL: &str = "traversal.txt";
+const COMPLETED: &str = "completed.txt";
+
+/// Interrupted jobs that are kept when a new rip starts, older ones are removed.
+const MAX_INTERRUPTED: usize = 5;
+
+/// A rip that can be resumed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Job {
+    pub entries: Vec<PathBuf>,
+    pub ripping: SampleRippingConfig,
+    pub naming: SampleNameConfig,
+    pub filters: FilterConfig,
+    /// Only rip some of the samples from these modules
+    #[serde(default)]
+    pub samples: Selection,
+    /// The selected folders were fully traversed
+    #[serde(default)]
+    pub traversed: bool,
+}
+
+impl Job {
+    /// The selection and configuration of a signal
+    pub fn new(signal: &Signal) -> Self {
+        Self {
+            entries: signal.entries.clone(),
+            ripping: signal.ripping.clone(),
+            naming: signal.naming.clone(),
+            filters: signal.filters.clone(),
+            samples: signal.samples.clone(),
+            traversed: false,
+        }
+    }
+
+    /// Load the last job that isn't being ripped, if there is one
+    pub fn load() -> Option<Self> {
+        latest().map(|(_, job, _)| job)
+    }
+
+    fn read(dir: &Path) -> Option<Self> {
+        let file = File::open(dir.join(JOB)).ok()?;
+
+        match serde_json::from_reader(BufReader::new(file)) {
+            Ok(job) => Some(job),
+            Err(error) => {
+                tracing::warn!("Could not load the last job: {error}");
+                None
+            }
+        }
+    }
+
+    /// Is there a job that can be resumed?
+    pub fn exists() -> bool {
+        Self::load().is_some()
+    }
+
+    fn save(&self, dir: &Path) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(dir.join(JOB))?);
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.flush()
+    }
+}
+
+impl From<Job> for Signal {
+    fn from(job: Job) -> Self {
+        Self {
+            entries: job.entries,
+            ripping: job.ripping,
+            naming: job.naming,
+            filters: job.filters,
+            samples: job.samples,
+            resume: true,
+        }
+    }
+}
+
+/// Records the progress of the current rip.
+///
+/// If the journal couldn't be created, the rip will continue without it.
+#[derive(Debug, Default)]
+pub struct Journal {
+    dir: Option<PathBuf>,
+    job: Option<Job>,
+    /// Files that were ripped by a previous attempt
+    completed: HashSet<PathBuf>,
+    writer: Mutex<Option<BufWriter<File>>>,
+    lock: Mutex<Option<Lock>>,
+}
+
+impl Journal {
+    /// Start a new journal, or continue the last one if the signal is resuming.
+    pub fn open(signal: &Signal) -> Self {
+        let result = match signal.resume {
+            true => Self::resume(),
+            false => Self::start(signal),
+        };
+
+        result.unwrap_or_else(|error| {
+            tracing::warn!("Could not create a journal, this rip can't be resumed: {error}");
+            Self::default()
+        })
+    }
+
+    fn start(signal: &Signal) -> io::Result<Self> {
+        // Jobs that are being ripped by another instance of XMODITS are left alone.
+        // Leave room for the new job, so it can be resumed as well.
+        let mut kept = 1;
+
+        for job in jobs().into_iter().rev() {
+            let Ok(Some(lock)) = Lock::try_acquire(&job.join(LOCK)) else {
+                continue;
+            };
+
+            if kept < MAX_INTERRUPTED && Job::read(&job).is_some() {
+                kept += 1;
+                continue;
+            }
+
+            if let Err(error) = remove(&job, lock) {
+                tracing::warn!("Could not remove the previous job: {error}");
+            }
+        }
+
+        let dir = dir().join(job_name());
+        std::fs::create_dir_all(&dir)?;
+
+        let lock = Lock::try_acquire(&dir.join(LOCK))?.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::WouldBlock, "The job is already locked")
+        })?;
+
+        let job = Job::new(signal);
+
+        job.save(&dir)?;
+
+        Ok(Self {
+            writer: Mutex::new(Some(BufWriter::new(File::create(dir.join(COMPLETED))?))),
+            job: Some(job),
+            completed: HashSet::new(),
+            dir: Some(dir),
+            lock: Mutex::new(Some(lock)),
+        })
+    }
+
+    fn resume() -> io::Result<Self> {
+        let (dir, job, lock) = latest().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "There isn't a job to resume")
+        })?;
+
+        let completed_path = dir.join(COMPLETED);
+
+        let completed = match File::open(&completed_path) {
+            Ok(file) => BufReader::new(file)
+                .lines()
+                .map_while(Result::ok)
+                .map(PathBuf::from)
+                .collect(),
+            Err(_) => HashSet::new(),
+        };
+
+        let writer = File::options()
+            .create(true)
+            .append(true)
+            .open(completed_path)?;
+
+        Ok(Self {
+            writer: Mutex::new(Some(BufWriter::new(writer))),
+            job: Some(job),
+            completed,
+            dir: Some(dir),
+            lock: Mutex::new(Some(lock)),
+        })
+    }
+
+    /// Was the file ripped by a previous attempt?
+    pub fn is_completed(&self, path: &Path) -> bool {
+        self.completed.contains(path)
+    }
+
+    /// Record that a file has been ripped without any errors
+    pub fn complete(&self, path: &Path) {
+        let mut writer = self.writer.lock();
+
+        if let Some(writer) = writer.as_mut() {
+            let result = writeln!(writer, "{}", path.display()).and_then(|_| writer.flush());
+
+            if let Err(error) = result {
+                tracing::warn!("Could not update the journal: {error}");
+            }
+        }
+    }
+
+    /// Returns the file that was produced by a previous traversal, if it was completed.
+    pub fn previous_traversal(&self) -> Option<File> {
+        match (&self.dir, &self.job) {
+            (Some(dir), Some(job)) if job.traversed => File::open(dir.join(TRAVERSAL)).ok(),
+            _ => None,
+        }
+    }
+
+    /// Create a file to store the traversed entries.
+    ///
+    /// If there isn't a journal, a temporary file is used instead.
+    pub fn traversal_file(&self) -> io::Result<File> {
+        match &self.dir {
+            Some(dir) => File::options()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(dir.join(TRAVERSAL)),
+            None => tempfile::tempfile(),
+        }
+    }
+
+    /// Record that every folder has been traversed,
+    /// so resuming the job doesn't have to traverse them again.
+    pub fn set_traversed(&self) {
+        if let (Some(dir), Some(job)) = (&self.dir, &self.job) {
+            let job = Job {
+                traversed: true,
+                ..job.clone()
+            };
+
+            if let Err(error) = job.save(dir) {
+                tracing::warn!("Could not update the journal: {error}");
+            }
+        }
+    }
+
+    /// The rip has finished, so the job isn't needed anymore.
+    pub fn finish(&self) {
+        // Close the file first, otherwise the folder can't be removed on Windows.
+        drop(self.writer.lock().take());
+
+        if let (Some(dir), Some(lock)) = (&self.dir, self.lock.lock().take()) {
+            if let Err(error) = remove(dir, lock) {
+                tracing::warn!("Could not remove the journal: {error}");
+            }
+        }
+    }
+}
+
+fn dir() -> PathBuf {
+    config::config_dir().join(JOBS_DIR)
+}
+
+/// Jobs are named after when they started, so they're sorted from oldest to newest
+fn job_name() -> String {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+
+    format!("{time:016}-{}", std::process::id())
+}
+
+/// Remove a job that's locked by the caller.
+///
+/// The job file is removed while the lock is held, so nobody can resume it once the lock is
+/// released. The lock has to be released before its folder can be removed on Windows.
+fn remove(dir: &Path, lock: Lock) -> io::Result<()> {
+    match std::fs::remove_file(dir.join(JOB)) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+        _ => (),
+    }
+
+    drop(lock);
+
+    match std::fs::remove_dir_all(dir) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+/// The folder of every job, from oldest to newest
+fn jobs() -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir()) else {
+        return Vec::new();
+    };
+
+    let mut jobs: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+
+    jobs.sort();
+    jobs
+}
+
+/// The last job that isn't being ripped, along with its folder.
+///
+/// The job stays locked until the lock is dropped.
+fn latest() -> Option<(PathBuf, Job, Lock)> {
+    jobs().into_iter().rev().find_map(|dir| {
+        let lock = Lock::try_acquire(&dir.join(LOCK)).ok()??;
+        let job = Job::read(&dir)?;
+        Some((dir, job, lock))
+    })
+}
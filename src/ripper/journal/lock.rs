@@ -0,0 +1,82 @@
+//! A lock that's held while a job is being ripped.
+//!
+//! The GUI and headless mode can rip at the same time, so a job that's locked
+//! must not be resumed or removed by anyone else.
+//!
+//! The lock is released by the operating system when the file is closed,
+//! so a job is unlocked even if XMODITS closed unexpectedly.
+
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// How long to wait before trying to acquire a lock again
+const RETRY: Duration = Duration::from_millis(50);
+
+#[derive(Debug)]
+pub struct Lock {
+    _file: File,
+}
+
+impl Lock {
+    /// Returns ``None`` if it's already locked.
+    pub fn try_acquire(path: &Path) -> io::Result<Option<Self>> {
+        Ok(open(path)?.map(|file| Self { _file: file }))
+    }
+
+    /// Wait until nobody else holds the lock.
+    pub fn acquire(path: &Path) -> io::Result<Self> {
+        loop {
+            if let Some(lock) = Self::try_acquire(path)? {
+                return Ok(lock);
+            }
+
+            std::thread::sleep(RETRY);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn open(path: &Path) -> io::Result<Option<File>> {
+    use std::os::unix::io::AsRawFd;
+
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0 {
+        return Ok(Some(file));
+    }
+
+    let error = io::Error::last_os_error();
+
+    match error.raw_os_error() {
+        Some(libc::EWOULDBLOCK) => Ok(None),
+        _ => Err(error),
+    }
+}
+
+#[cfg(windows)]
+fn open(path: &Path) -> io::Result<Option<File>> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use windows_sys::Win32::Foundation::ERROR_SHARING_VIOLATION;
+
+    // Nobody else can open the file while it isn't shared
+    let result = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .share_mode(0)
+        .open(path);
+
+    match result {
+        Ok(file) => Ok(Some(file)),
+        Err(error) if error.raw_os_error() == Some(ERROR_SHARING_VIOLATION as i32) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
@@ -1,4 +1,5 @@
 use data::config;
+use data::history::Selection;
 use std::path::PathBuf;
 
 /// Constructed and sent by the main GUI
@@ -8,18 +9,45 @@ pub struct Signal {
     pub entries: Vec<PathBuf>,
     pub ripping: config::SampleRippingConfig,
     pub naming: config::SampleNameConfig,
+    pub filters: config::FilterConfig,
+    /// Only rip some of the samples from these modules
+    pub samples: Selection,
+    /// Continue the last job instead of starting a new one
+    pub resume: bool,
 }
 
 impl Signal {
+    /// Check that the rip can start.
+    ///
+    /// Custom filters that don't compile would otherwise rip every file,
+    /// and an invalid template would name the samples differently.
+    /// The export format might not be available in this build.
+    pub fn validate(&self) -> Result<(), String> {
+        self.filters.build()?;
+        self.naming.validate()?;
+
+        let format = self.ripping.exported_format;
+
+        if !format.is_available() {
+            return Err(format!("XMODITS was built without {format} support"));
+        }
+
+        Ok(())
+    }
+
     pub fn new(
         entries: Vec<PathBuf>,
         ripping: config::SampleRippingConfig,
         naming: config::SampleNameConfig,
+        filters: config::FilterConfig,
     ) -> Self {
         Self {
             ripping,
             naming,
+            filters,
             entries,
+            samples: Selection::new(),
+            resume: false,
         }
     }
 }
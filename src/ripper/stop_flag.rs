@@ -7,18 +7,31 @@
 //!
 //! The crash handler MUST be able to abort the ripping process, otherwise we could have multiple panics
 //! (and multiple error boxes).
+//!
+//! The ripping process can also be paused. Workers finish the module they're ripping, then
+//! wait until the process is resumed or stopped.
 
 use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
 
 static STOP_FLAG: AtomicU8 = AtomicU8::new(0);
 
 const NONE: u8 = 0;
 const CANCEL: u8 = 1;
 const ABORT: u8 = 2;
+const PAUSE: u8 = 3;
+
+/// How often a paused worker checks if it can continue
+const PAUSE_INTERVAL: Duration = Duration::from_millis(100);
 
 /// Has the stopped flag been set?
 pub fn is_set() -> bool {
-    STOP_FLAG.load(Ordering::Acquire) != NONE
+    matches!(STOP_FLAG.load(Ordering::Acquire), CANCEL | ABORT)
+}
+
+/// Has the paused flag been set?
+pub fn is_paused() -> bool {
+    STOP_FLAG.load(Ordering::Acquire) == PAUSE
 }
 
 /// Has the cancelled flag been set?
@@ -31,6 +44,15 @@ pub fn is_aborted() -> bool {
     STOP_FLAG.load(Ordering::Acquire) == ABORT
 }
 
+/// Block the current thread while the paused flag is set.
+///
+/// Returns immediately if the process is stopped.
+pub fn wait_while_paused() {
+    while is_paused() {
+        std::thread::sleep(PAUSE_INTERVAL);
+    }
+}
+
 /// Reset the flag back to its original state (only if abort flag isn't set)
 pub fn reset() {
     if !is_aborted() {
@@ -57,6 +79,16 @@ pub fn set_cancel() {
     }
 }
 
+/// Set flag to pause (only if it hasn't been stopped)
+pub fn set_pause() {
+    let _ = STOP_FLAG.compare_exchange(NONE, PAUSE, Ordering::AcqRel, Ordering::Acquire);
+}
+
+/// Clear the pause flag (only if it hasn't been stopped)
+pub fn clear_pause() {
+    let _ = STOP_FLAG.compare_exchange(PAUSE, NONE, Ordering::AcqRel, Ordering::Acquire);
+}
+
 #[derive(Debug, Clone, Copy)]
 #[non_exhaustive]
 #[repr(u8)]
@@ -64,6 +96,7 @@ pub enum StopFlag {
     None = NONE,
     Cancel = CANCEL,
     Abort = ABORT,
+    Pause = PAUSE,
 }
 
 pub fn get_flag() -> StopFlag {
@@ -71,6 +104,7 @@ pub fn get_flag() -> StopFlag {
         NONE => StopFlag::None,
         CANCEL => StopFlag::Cancel,
         ABORT => StopFlag::Abort,
+        PAUSE => StopFlag::Pause,
         _ => unreachable!(),
     }
 }
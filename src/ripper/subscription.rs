@@ -8,7 +8,7 @@ use tracing::{error, info};
 
 pub use super::extraction::{self, ErrorHandler, Failed, Message as ThreadMessage, StopMessage};
 use super::stop_flag::{self, StopFlag};
-use super::Signal;
+use super::{history, Job, Signal};
 
 /// Messages emitted by subscription
 #[derive(Clone, Debug)]
@@ -95,6 +95,7 @@ pub fn subscription() -> Subscription<Message> {
                 total_errors: u64,
                 timer: Time,
                 destination: PathBuf,
+                job: Option<Job>,
             },
         }
 
@@ -118,6 +119,7 @@ pub fn subscription() -> Subscription<Message> {
                     if let Some(config) = start_msg.recv().await {
                         let total = config.entries.len() as u64;
                         let destination = config.ripping.destination.clone();
+                        let job = Job::new(&config);
                         let (tx, rx) = mpsc::unbounded_channel();
 
                         // The ripping process is delegated by the subscription to a separate thread.
@@ -135,6 +137,7 @@ pub fn subscription() -> Subscription<Message> {
                             total_errors: 0,
                             timer: Time::init(),
                             destination,
+                            job: Some(job),
                         };
                     }
                 }
@@ -146,12 +149,33 @@ pub fn subscription() -> Subscription<Message> {
                     total,
                     timer,
                     destination,
+                    job,
                 } => match ripping_msg.recv().await {
-                    Some(ThreadMessage::Progress(error)) => {
+                    Some(ThreadMessage::Progress(report)) => {
                         *progress += 1;
                         let percentage: f32 = (*progress as f32 / *total as f32) * 100.0;
 
-                        if let Some(failed) = error {
+                        for path in &report.renamed {
+                            info!("Renamed to avoid a collision: {}", path.display());
+                        }
+
+                        for path in &report.skipped {
+                            info!("Skipped, file already exists: {}", path.display());
+                        }
+
+                        for path in &report.noise {
+                            info!("Most likely static noise: {}", path.display());
+                        }
+
+                        if !report.duplicates.is_empty() {
+                            info!(
+                                "{} duplicate sample(s) in {}",
+                                report.duplicates.len(),
+                                report.source.display()
+                            );
+                        }
+
+                        if let Some(failed) = report.failed {
                             error!("{}", &failed);
                             *total_errors += 1;
                             error_handler.push(failed).await;
@@ -166,6 +190,9 @@ pub fn subscription() -> Subscription<Message> {
                         *total = new_total;
                         *progress = 0;
                     }
+                    Some(ThreadMessage::Found(found)) => {
+                        *total += found;
+                    }
                     Some(ThreadMessage::Info(info)) => {
                         let _ = output.try_send(Message::Info(info));
                     }
@@ -177,6 +204,10 @@ pub fn subscription() -> Subscription<Message> {
                             StopMessage::Cancel => CompleteState::Cancelled,
                         };
 
+                        if let Some(job) = job.take() {
+                            history::record(job, &completed_state, timer).await;
+                        }
+
                         let msg = Message::Done {
                             state: completed_state,
                             time: std::mem::take(timer),
@@ -194,9 +225,14 @@ pub fn subscription() -> Subscription<Message> {
                     Some(ThreadMessage::Done) => {
                         timer.stop();
                         let error = std::mem::take(error_handler);
+                        let completed_state = CompleteState::from(error);
+
+                        if let Some(job) = job.take() {
+                            history::record(job, &completed_state, timer).await;
+                        }
 
                         let msg = Message::Done {
-                            state: CompleteState::from(error),
+                            state: completed_state,
                             time: std::mem::take(timer),
                             destination: std::mem::take(destination),
                         };
@@ -215,11 +251,15 @@ pub fn subscription() -> Subscription<Message> {
                         let error = std::mem::take(error_handler);
 
                         let completed_state: CompleteState = match stop_flag::get_flag() {
-                            StopFlag::None => CompleteState::from(error),
+                            StopFlag::None | StopFlag::Pause => CompleteState::from(error),
                             StopFlag::Cancel => CompleteState::Cancelled,
                             StopFlag::Abort => CompleteState::Aborted,
                         };
 
+                        if let Some(job) = job.take() {
+                            history::record(job, &completed_state, timer).await;
+                        }
+
                         let msg = Message::Done {
                             state: completed_state,
                             time: std::mem::take(timer),
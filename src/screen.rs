@@ -5,6 +5,7 @@ pub mod build_info;
 pub mod config;
 pub mod crash;
 pub mod entry;
+pub mod history;
 pub mod ripping;
 pub mod sample_player;
 pub mod settings;
@@ -1,5 +1,7 @@
 //! Configure the behaviour of XMODITS' ripping routine
 
+pub mod custom_filters;
 pub mod name_preview;
+pub mod processing;
 pub mod sample_naming;
 pub mod sample_ripping;
@@ -5,69 +5,95 @@ mod file_name;
 mod file_size;
 mod regex;
 
-use data::config::filters::{Filter, Name, Size};
-use iced::Task;
-use std::path::Path;
+use data::config::FilterConfig;
+use iced::widget::{column, scrollable};
+use iced::{Length, Task};
 
-use iced::widget::{column, horizontal_rule, row};
-
-use crate::icon;
-use crate::utils::{extension, filename};
-use crate::widget::helpers::{control_filled, text_icon};
-use crate::widget::{helpers::control, Element};
+use crate::widget::Element;
 
 use self::file_date::DateFilter;
 use self::file_name::NameFilter;
-
-pub struct Filters(Vec<Box<dyn Filter>>);
-
-impl Filters {
-    pub fn new(filters: Vec<Box<dyn Filter>>) -> Self {
-        Self(filters)
-    }
-
-    pub fn matches(&self, path: &Path) -> bool {
-        for filter in &self.0 {
-            if !filter.matches(path) {
-                return false;
-            }
-        }
-        true
-    }
-}
+use self::regex::RegexFilter;
 
 #[derive(Debug, Clone)]
 pub enum Message {
+    FileDate(file_date::Message),
     FileSize(file_size::Message),
     FileName(file_name::Message),
-    FileDate(file_date::Message),
+    Regex(regex::Message),
 }
 
 #[derive(Default, Debug)]
 pub struct CustomFilters {
-    pub filesize: Size,
-    pub filename: NameFilter,
-    pub date: DateFilter,
+    pub config: FilterConfig,
+    date: DateFilter,
+    filename: NameFilter,
+    regex: RegexFilter,
 }
 
 impl CustomFilters {
+    pub fn new(config: FilterConfig) -> Self {
+        Self {
+            regex: RegexFilter::new(&config.regex),
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Why the filters can't be used, if they're invalid
+    pub fn error(&self) -> Option<&str> {
+        self.regex.error().filter(|_| self.config.enable_regex)
+    }
+
     pub fn update(&mut self, msg: Message) -> Task<Message> {
+        tracing::info!("{:?}", &msg);
+
         match msg {
-            Message::FileSize(filesize) => file_size::update(&mut self.filesize, filesize),
-            Message::FileName(filename) => return self.filename.update(filename).map(Message::FileName),
-            Message::FileDate(filedate) => self.date.update(filedate),
+            Message::FileDate(date) => self.date.update(&mut self.config, date),
+            Message::FileSize(filesize) => file_size::update(&mut self.config, filesize),
+            Message::FileName(filename) => {
+                return self
+                    .filename
+                    .update(&mut self.config, filename)
+                    .map(Message::FileName)
+            }
+            Message::Regex(regex) => {
+                return self
+                    .regex
+                    .update(&mut self.config, regex)
+                    .map(Message::Regex)
+            }
         }
         Task::none()
     }
+
+    pub fn view(&self) -> Element<Message> {
+        scrollable(
+            column![
+                self.view_file_date(),
+                self.view_file_size(),
+                self.view_file_name(),
+                self.view_regex()
+            ]
+            .spacing(10),
+        )
+        .height(Length::Fill)
+        .into()
+    }
+
+    pub fn view_file_date(&self) -> Element<Message> {
+        self.date.view(&self.config).map(Message::FileDate)
+    }
+
     pub fn view_file_size(&self) -> Element<Message> {
-        file_size::view(&self.filesize).map(Message::FileSize)
+        file_size::view(&self.config).map(Message::FileSize)
     }
 
     pub fn view_file_name(&self) -> Element<Message> {
-        self.filename.view().map(Message::FileName)
+        self.filename.view(&self.config).map(Message::FileName)
     }
 
-    pub fn view_file_date(&self) -> Element<Message> {
-        self.date.view().map(Message::FileDate)
+    pub fn view_regex(&self) -> Element<Message> {
+        self.regex.view(&self.config).map(Message::Regex)
     }
 }
@@ -1,120 +1,148 @@
-use std::fmt::Display;
-
+use crate::style;
 use crate::widget::helpers::control;
 use crate::widget::Element;
-use crate::{icon, theme};
-use crate::{theme::TextInputStyle, widget::helpers::text_icon};
-
-use chrono::NaiveDate;
-use data::config::filters::date::{Condition, Date};
-
-use iced::widget::tooltip::Position;
-use iced::widget::{
-    button, checkbox, column, horizontal_rule, pick_list, row, slider, text, text_input, tooltip,
-};
+use data::config::filters::date::{Condition, NaiveDate, Range};
+use data::config::filters::Date;
+use data::config::FilterConfig;
+use iced::widget::{checkbox, column, pick_list, row, text, text_input};
+use iced::{Alignment, Length};
 
-// use iced_aw::{date_picker::Date as icedDate, helpers::date_picker};
-
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Message {
-    DatePicker(DateKind),
-    CancelPicker,
-    SubmitDate {
-        kind: DateKind,
-        // date: iced_aw::core::date::Date,
-    },
+    Enable(bool),
     Condition(Condition),
+    Range(Range),
+    After(String),
+    Before(String),
+    Days(u32),
+    Ignore,
 }
 
+/// Dates are typed in as YYYY-MM-DD.
+///
+/// The text is kept while it's being edited, since it isn't always a valid date.
 #[derive(Debug, Default)]
 pub struct DateFilter {
-    inner: Date,
-    date_picker: DateKind,
-    show_date_picker: bool,
-}
-
-#[derive(Debug, Default, Clone, Copy)]
-pub enum DateKind {
-    #[default]
-    Before,
-    After,
+    after: Option<String>,
+    before: Option<String>,
 }
 
 impl DateFilter {
-    pub fn update(&mut self, msg: Message) {
+    pub fn update(&mut self, config: &mut FilterConfig, msg: Message) {
+        let filter = &mut config.date;
+
         match msg {
-            Message::DatePicker(datekind) => {
-                self.date_picker = datekind;
-                self.show_date_picker = true;
+            Message::Enable(enable) => config.enable_date = enable,
+            Message::Condition(condition) => filter.condition = condition,
+            Message::Range(range) => filter.range = range,
+            Message::After(input) => {
+                if let Some(date) = parse(&input) {
+                    filter.after = date;
+                }
+                self.after = Some(input);
             }
-            Message::CancelPicker => self.show_date_picker = false,
-            Message::SubmitDate { .. } => {
-                // match kind {
-                //     DateKind::Before => self.inner.before = Some(date.into()),
-                //     DateKind::After => self.inner.after = Some(date.into()),
-                // }
-
-                self.show_date_picker = false;
+            Message::Before(input) => {
+                if let Some(date) = parse(&input) {
+                    filter.before = date;
+                }
+                self.before = Some(input);
             }
-            Message::Condition(condition) => self.inner.condition = condition,
+            Message::Days(days) => filter.days = days,
+            Message::Ignore => (),
         }
     }
 
-    pub fn view(&self) -> Element<Message> {
-        // IDEA: buttons could have a tooltip showing the date&time in more detail
-        // TODO: buttons must bring up date and time picker, should be an overlay
-        let after_date = self.inner.after();
-        let after_date_long = after_date.format("%A, %-d %B, %C%y");
-
-        let before_date = self.inner.before();
-        let before_date_long = before_date.format("%A, %-d %B, %C%y");
-
-        let settings = row![
-            pick_list(Condition::ALL, Some(self.inner.condition), Message::Condition),
-            tooltip(
-                button(text(after_date.to_string()))
-                    .on_press(Message::DatePicker(DateKind::After))
-                    .padding(8)
-                    .style(theme::Button::Dark),
-                after_date_long,
-                Position::Bottom,
-            )
-            .padding(6)
-            .style(theme::Container::Frame),
-            "-",
-            tooltip(
-                button(text(before_date))
-                    .on_press(Message::DatePicker(DateKind::Before))
-                    .padding(8)
-                    .style(theme::Button::Dark),
-                before_date_long,
-                Position::Bottom,
-            )
-            .padding(6)
-            .style(theme::Container::Frame),
+    pub fn view<'a>(&'a self, config: &'a FilterConfig) -> Element<'a, Message> {
+        let filter = &config.date;
+
+        let range = match filter.range {
+            Range::Between => row![
+                date_input(
+                    filter.after(),
+                    self.after.as_deref(),
+                    filter.after,
+                    Message::After
+                ),
+                "and",
+                date_input(
+                    filter.before(),
+                    self.before.as_deref(),
+                    filter.before,
+                    Message::Before
+                ),
+            ],
+            Range::LastDays => row![
+                text_input("", &filter.days.to_string())
+                    .width(80)
+                    .on_input(|input| {
+                        if input.is_empty() {
+                            return Message::Days(0);
+                        }
+
+                        input
+                            .parse::<u32>()
+                            .map(Message::Days)
+                            .unwrap_or(Message::Ignore)
+                    }),
+                "days"
+            ],
+        }
+        .spacing(8)
+        .align_y(Alignment::Center);
+
+        let settings = column![
+            checkbox("Enabled", config.enable_date).on_toggle(Message::Enable),
+            row![
+                pick_list(Condition::ALL, Some(filter.condition), Message::Condition),
+                pick_list(Range::ALL, Some(filter.range), Message::Range),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+            range,
         ]
-        .align_items(iced::Alignment::Center)
+        .push_maybe(
+            self.invalid()
+                .then(|| text("Dates must be written as YYYY-MM-DD.").style(style::text::error)),
+        )
+        .push("Hint: Leave a date empty to remove that limit.")
+        .push("Note: If the creation date isn't available, the modified date is used.")
         .spacing(8);
 
         control("File Date", settings).into()
     }
-}
 
-// pub fn show_date_picker<'a>(
-//     filter: &'a DateFilter,
-//     underlay: impl Into<Element<'a, Message>>,
-// ) -> Element<'a, Message> {
-//     let date = match filter.date_picker {
-//         DateKind::Before => filter.inner.before(),
-//         DateKind::After => filter.inner.after(),
-//     };
+    fn invalid(&self) -> bool {
+        [&self.after, &self.before]
+            .into_iter()
+            .flatten()
+            .any(|input| parse(input).is_none())
+    }
+}
 
-//     let show = filter.show_date_picker;
-//     let kind = filter.date_picker;
+/// Parse the input of a date.
+///
+/// An empty input removes the date.
+fn parse(input: &str) -> Option<Option<NaiveDate>> {
+    match input.trim().is_empty() {
+        true => Some(None),
+        false => Date::parse(input).map(Some),
+    }
+}
 
-//     date_picker(show, date, underlay, Message::CancelPicker, move |date| Message::SubmitDate {
-//         kind,
-//         date,
-//     })
-//     .into()
-// }
+fn date_input<'a>(
+    fallback: NaiveDate,
+    input: Option<&'a str>,
+    date: Option<NaiveDate>,
+    on_input: fn(String) -> Message,
+) -> Element<'a, Message> {
+    let value = match input {
+        Some(input) => input.to_owned(),
+        None => date.map(|date| date.to_string()).unwrap_or_default(),
+    };
+
+    text_input(&fallback.to_string(), &value)
+        .width(Length::Fill)
+        .on_input(on_input)
+        .padding(8)
+        .into()
+}
@@ -1,115 +1,174 @@
-use crate::theme::TextInputStyle;
-use crate::widget::helpers::{control_filled, fill_container, text_elem};
+use crate::style;
+use crate::widget::helpers::{control, fill_container, text_adv};
 use crate::widget::Element;
-use crate::{theme, widget::helpers::control};
+use data::config::filters::name::Outcome;
 use data::config::filters::Name;
-use iced::widget::{
-    button, checkbox, column, container, horizontal_rule, pick_list, row, scrollable, slider, text,
-    text_input, Space,
-};
-use iced::{Alignment, Task, Length};
+use data::config::FilterConfig;
+use iced::widget::{button, checkbox, column, pick_list, row, scrollable, text_input, Space};
+use iced::{Alignment, Length, Task};
 use once_cell::sync::Lazy;
 
 static TEXTBOX_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
 
 #[derive(Debug, Clone)]
 pub enum Message {
+    Enable(bool),
     Input(String),
     CaseSensitive(bool),
     Outcome(Outcome),
     Condition(Condition),
-    Check(),
     Add,
+    Remove(Condition, usize),
+    Clear,
 }
 
 #[derive(Debug, Default)]
 pub struct NameFilter {
     buffer: String,
-    case_sensitive: bool,
     condition: Condition,
-    outcome: Outcome,
-    filters: Name,
 }
 
 impl NameFilter {
-    pub fn update(&mut self, msg: Message) -> Task<Message> {
+    pub fn update(&mut self, config: &mut FilterConfig, msg: Message) -> Task<Message> {
+        let filter = &mut config.name;
+
         match msg {
+            Message::Enable(enable) => config.enable_name = enable,
             Message::Input(word) => self.buffer = word,
-            Message::CaseSensitive(case) => self.case_sensitive = case,
-            Message::Outcome(outcome) => self.outcome = outcome,
+            Message::CaseSensitive(case) => filter.case_sensitive = case,
+            Message::Outcome(outcome) => filter.outcome = outcome,
             Message::Condition(condition) => self.condition = condition,
-            Message::Check() => (),
             Message::Add => {
-                if self.buffer.is_empty() {
+                let word = self.buffer.trim();
+
+                if word.is_empty() {
                     return text_input::focus(TEXTBOX_ID.clone());
                 }
+
+                let list = self.condition.list_mut(filter);
+
+                if !list.iter().any(|f| f == word) {
+                    list.push(word.to_owned());
+                }
+
+                self.buffer.clear();
+            }
+            Message::Remove(condition, index) => {
+                let list = condition.list_mut(filter);
+
+                if index < list.len() {
+                    list.remove(index);
+                }
             }
+            Message::Clear => *filter = Name {
+                case_sensitive: filter.case_sensitive,
+                outcome: filter.outcome,
+                ..Default::default()
+            },
         }
         Task::none()
     }
 
-    pub fn view(&self) -> Element<Message> {
+    pub fn view<'a>(&'a self, config: &'a FilterConfig) -> Element<'a, Message> {
+        let filter = &config.name;
+
         let input_box = text_input("Filter...", &self.buffer)
             .id(TEXTBOX_ID.clone())
-            .style(theme::TextInputStyle::Inverted)
             .width(Length::Fill)
             .on_input(Message::Input)
+            .on_submit(Message::Add)
             .padding(8);
 
         let set_conditions = row![
-            pick_list(Outcome::ALL, Some(self.outcome), Message::Outcome),
+            pick_list(Outcome::ALL, Some(filter.outcome), Message::Outcome),
             pick_list(Condition::ALL, Some(self.condition), Message::Condition),
         ]
         .spacing(5)
         .width(Length::Fill);
 
-        let add_buttons = row![
-            button("Add").on_press(Message::Add),
-            button("Add AND"),
-            button("Add OR")
-        ]
-        .align_items(Alignment::Center)
-        .spacing(5);
+        let rules = Condition::ALL.iter().flat_map(|condition| {
+            condition
+                .list(filter)
+                .iter()
+                .enumerate()
+                .map(move |(index, word)| view_rule(*condition, index, word))
+        });
 
         let filter_list = fill_container(scrollable(
-            container(self.filters.contains.iter().fold(column![].spacing(4), |f, y| {
-                f.push(row![
-                    checkbox("", false, |_| Message::Check()),
-                    button(y.as_ref()).style(theme::Button::HyperlinkInverted)
-                ])
-            }))
-            .width(Length::Fill),
+            column(rules).spacing(4).width(Length::Fill),
         ))
+        .height(150)
         .padding(8)
-        .style(theme::Container::Black);
+        .style(style::container::black);
 
         let settings = column![
+            checkbox("Enabled", config.enable_name).on_toggle(Message::Enable),
             set_conditions,
-            input_box,
-            checkbox("Case Sensitive", self.case_sensitive, Message::CaseSensitive),
-            add_buttons,
+            row![input_box, button("Add").on_press(Message::Add)]
+                .spacing(5)
+                .align_y(Alignment::Center),
+            checkbox("Case Sensitive", filter.case_sensitive).on_toggle(Message::CaseSensitive),
             filter_list,
             row![
-                button("Remove Selected"),
                 Space::with_width(Length::Fill),
                 button("Clear")
+                    .on_press(Message::Clear)
+                    .style(style::button::cancel)
             ],
         ]
         .spacing(8);
-        control_filled("File Name", settings).into()
+
+        control("File Name", settings).into()
     }
 }
 
+fn view_rule(condition: Condition, index: usize, word: &str) -> Element<Message> {
+    row![
+        text_adv(format!("{condition}: \"{word}\"")),
+        Space::with_width(Length::Fill),
+        button("Remove")
+            .on_press(Message::Remove(condition, index))
+            .style(style::button::hyperlink_inverted),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(5)
+    .into()
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum Condition {
     #[default]
     Contains,
     StartsWith,
     EndsWith,
+    Extension,
 }
 
 impl Condition {
-    const ALL: &'static [Self] = &[Self::Contains, Self::StartsWith, Self::EndsWith];
+    const ALL: &'static [Self] = &[
+        Self::Contains,
+        Self::StartsWith,
+        Self::EndsWith,
+        Self::Extension,
+    ];
+
+    fn list(self, filter: &Name) -> &Vec<String> {
+        match self {
+            Condition::Contains => &filter.contains,
+            Condition::StartsWith => &filter.starts_with,
+            Condition::EndsWith => &filter.ends_with,
+            Condition::Extension => &filter.extensions,
+        }
+    }
+
+    fn list_mut(self, filter: &mut Name) -> &mut Vec<String> {
+        match self {
+            Condition::Contains => &mut filter.contains,
+            Condition::StartsWith => &mut filter.starts_with,
+            Condition::EndsWith => &mut filter.ends_with,
+            Condition::Extension => &mut filter.extensions,
+        }
+    }
 }
 
 impl std::fmt::Display for Condition {
@@ -121,30 +180,7 @@ impl std::fmt::Display for Condition {
                 Condition::Contains => "Contains",
                 Condition::StartsWith => "Starts With",
                 Condition::EndsWith => "Ends with",
-            }
-        )
-    }
-}
-
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Outcome {
-    #[default]
-    Allow,
-    Reject,
-}
-
-impl Outcome {
-    const ALL: &'static [Self] = &[Self::Allow, Self::Reject];
-}
-
-impl std::fmt::Display for Outcome {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Self::Allow => "Allow if it",
-                Self::Reject => "Reject if it",
+                Condition::Extension => "Has Extension",
             }
         )
     }
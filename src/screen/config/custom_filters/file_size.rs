@@ -1,13 +1,13 @@
-use std::fmt::Display;
-
 use crate::widget::helpers::control;
-use crate::theme::TextInputStyle;
 use crate::widget::Element;
-use data::config::filters::{size::Modifier, Size};
-use iced::widget::{column, pick_list, row, slider, text_input, text, horizontal_rule};
+use data::config::filters::size::Modifier;
+use data::config::FilterConfig;
+use iced::widget::{checkbox, column, pick_list, row, text_input};
+use iced::Alignment;
 
 #[derive(Debug, Clone, Copy)]
 pub enum Message {
+    Enable(bool),
     SetMin(u64),
     SetMax(u64),
     SetMinModifier(Modifier),
@@ -15,8 +15,11 @@ pub enum Message {
     Ignore,
 }
 
-pub fn view(filter: &Size) -> Element<Message> {
+pub fn view(config: &FilterConfig) -> Element<Message> {
+    let filter = &config.size;
+
     let settings = column![
+        checkbox("Enabled", config.enable_size).on_toggle(Message::Enable),
         row![
             "Min:",
             text_input("", &format!("{}", filter.min)).on_input(|input| {
@@ -27,12 +30,11 @@ pub fn view(filter: &Size) -> Element<Message> {
                     .parse::<u64>()
                     .map(Message::SetMin)
                     .unwrap_or(Message::Ignore)
-            })
-            .style(TextInputStyle::Inverted),
+            }),
             pick_list(Modifier::ALL, Some(filter.min_modifier), Message::SetMinModifier)
         ]
         .spacing(8)
-        .align_items(iced::Alignment::Center),
+        .align_y(Alignment::Center),
         row![
             "Max:",
             text_input("", &format!("{}", filter.max)).on_input(|input| {
@@ -44,26 +46,24 @@ pub fn view(filter: &Size) -> Element<Message> {
                     .parse::<u64>()
                     .map(Message::SetMax)
                     .unwrap_or(Message::Ignore)
-            })
-            .style(TextInputStyle::Inverted),
+            }),
             pick_list(Modifier::ALL, Some(filter.max_modifier), Message::SetMaxModifier)
         ]
         .spacing(8)
-        .align_items(iced::Alignment::Center),
-        // horizontal_rule(1),
-        // text("Hint: 1000 bytes = 1 KB, 1000 KB = 1 MB"),
+        .align_y(Alignment::Center),
+        "Hint: Set \"Max\" to 0 to remove the upper limit.",
     ]
     .spacing(8);
     control("File Size", settings).into()
 }
 
-pub fn update(filter: &mut Size, msg: Message) {
-    tracing::info!("{:?}", msg);
+pub fn update(config: &mut FilterConfig, msg: Message) {
     match msg {
-        Message::SetMin(min) => filter.min = min,
-        Message::SetMax(max) => filter.max = max,
-        Message::SetMinModifier(modi) => filter.min_modifier = modi,
-        Message::SetMaxModifier(modi) => filter.max_modifier = modi,
+        Message::Enable(enable) => config.enable_size = enable,
+        Message::SetMin(min) => config.size.min = min,
+        Message::SetMax(max) => config.size.max = max,
+        Message::SetMinModifier(modi) => config.size.min_modifier = modi,
+        Message::SetMaxModifier(modi) => config.size.max_modifier = modi,
         Message::Ignore => (),
     }
 }
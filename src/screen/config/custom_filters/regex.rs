@@ -0,0 +1,258 @@
+use std::path::Path;
+
+use crate::style;
+use crate::widget::helpers::{control, fill_container, text_adv};
+use crate::widget::Element;
+use data::config::filters::regex::{CompiledRegex, Target};
+use data::config::filters::{Filter, Regex};
+use data::config::FilterConfig;
+use iced::widget::{button, checkbox, column, pick_list, row, scrollable, text, text_input, Space};
+use iced::{Alignment, Length, Task};
+use once_cell::sync::Lazy;
+
+static TEXTBOX_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Enable(bool),
+    Input(String),
+    Kind(Kind),
+    CaseInsensitive(bool),
+    Target(Target),
+    Add,
+    Remove(Kind, usize),
+    Clear,
+    TestPath(String),
+}
+
+#[derive(Debug, Default)]
+pub struct RegexFilter {
+    buffer: String,
+    kind: Kind,
+    error: Option<String>,
+    test_path: String,
+    /// The patterns are only compiled when they change
+    compiled: Option<Result<CompiledRegex, String>>,
+}
+
+impl RegexFilter {
+    pub fn new(filter: &Regex) -> Self {
+        Self {
+            compiled: Some(filter.compile()),
+            ..Default::default()
+        }
+    }
+
+    /// Why the patterns can't be used, if they don't compile
+    pub fn error(&self) -> Option<&str> {
+        match &self.compiled {
+            Some(Err(error)) => Some(error),
+            _ => None,
+        }
+    }
+
+    pub fn update(&mut self, config: &mut FilterConfig, msg: Message) -> Task<Message> {
+        let changes_patterns = matches!(
+            msg,
+            Message::CaseInsensitive(_)
+                | Message::Target(_)
+                | Message::Add
+                | Message::Remove(..)
+                | Message::Clear
+        );
+
+        let filter = &mut config.regex;
+
+        match msg {
+            Message::Enable(enable) => config.enable_regex = enable,
+            Message::Input(pattern) => {
+                self.error = None;
+                self.buffer = pattern;
+            }
+            Message::Kind(kind) => self.kind = kind,
+            Message::CaseInsensitive(case) => filter.case_insensitive = case,
+            Message::Target(target) => filter.target = target,
+            Message::Add => {
+                let pattern = self.buffer.trim();
+
+                if pattern.is_empty() {
+                    return text_input::focus(TEXTBOX_ID.clone());
+                }
+
+                if let Err(error) = filter.validate(pattern) {
+                    self.error = Some(error);
+                    return text_input::focus(TEXTBOX_ID.clone());
+                }
+
+                let list = self.kind.list_mut(filter);
+
+                if !list.iter().any(|f| f == pattern) {
+                    list.push(pattern.to_owned());
+                }
+
+                self.buffer.clear();
+            }
+            Message::Remove(kind, index) => {
+                let list = kind.list_mut(filter);
+
+                if index < list.len() {
+                    list.remove(index);
+                }
+            }
+            Message::Clear => {
+                filter.include.clear();
+                filter.exclude.clear();
+            }
+            Message::TestPath(path) => self.test_path = path,
+        }
+
+        if changes_patterns {
+            self.compiled = Some(config.regex.compile());
+        }
+
+        Task::none()
+    }
+
+    pub fn view<'a>(&'a self, config: &'a FilterConfig) -> Element<'a, Message> {
+        let filter = &config.regex;
+
+        let input_box = text_input("Regular expression...", &self.buffer)
+            .id(TEXTBOX_ID.clone())
+            .width(Length::Fill)
+            .on_input(Message::Input)
+            .on_submit(Message::Add)
+            .padding(8);
+
+        let options = row![
+            pick_list(Kind::ALL, Some(self.kind), Message::Kind),
+            pick_list(Target::ALL, Some(filter.target), Message::Target),
+        ]
+        .spacing(5)
+        .width(Length::Fill);
+
+        let error = self
+            .error
+            .as_deref()
+            .map(|error| text(error).style(style::text::error));
+
+        let patterns = Kind::ALL.iter().flat_map(|kind| {
+            kind.list(filter)
+                .iter()
+                .enumerate()
+                .map(move |(index, pattern)| view_pattern(*kind, index, pattern))
+        });
+
+        let pattern_list =
+            fill_container(scrollable(column(patterns).spacing(4).width(Length::Fill)))
+                .height(120)
+                .padding(8)
+                .style(style::container::black);
+
+        let test_path = text_input("Test against a path...", &self.test_path)
+            .width(Length::Fill)
+            .on_input(Message::TestPath)
+            .padding(8);
+
+        let settings = column![
+            checkbox("Enabled", config.enable_regex).on_toggle(Message::Enable),
+            options,
+            row![input_box, button("Add").on_press(Message::Add)]
+                .spacing(5)
+                .align_y(Alignment::Center),
+        ]
+        .push_maybe(error)
+        .push(
+            checkbox("Case Insensitive", filter.case_insensitive)
+                .on_toggle(Message::CaseInsensitive),
+        )
+        .push(pattern_list)
+        .push_maybe(
+            self.error()
+                .map(|error| text(format!("Invalid patterns: {error}")).style(style::text::error)),
+        )
+        .push(row![
+            Space::with_width(Length::Fill),
+            button("Clear")
+                .on_press(Message::Clear)
+                .style(style::button::cancel)
+        ])
+        .push(test_path)
+        .push_maybe(self.preview())
+        .spacing(8);
+
+        control("Regex", settings).into()
+    }
+}
+
+impl RegexFilter {
+    /// Show if the test path would be kept by the filter
+    fn preview(&self) -> Option<Element<Message>> {
+        let path = self.test_path.trim();
+
+        if path.is_empty() {
+            return None;
+        }
+
+        let result = match self.compiled.as_ref()? {
+            // The test path is relative to the scanned folder
+            Ok(regex) => match regex.matches(Path::new(path), Path::new("")) {
+                true => text("Allowed: this file would be ripped."),
+                false => text("Rejected: this file would be skipped.").style(style::text::warning),
+            },
+            Err(error) => text(error).style(style::text::error),
+        };
+
+        Some(result.into())
+    }
+}
+
+fn view_pattern(kind: Kind, index: usize, pattern: &str) -> Element<Message> {
+    row![
+        text_adv(format!("{kind}: {pattern}")),
+        Space::with_width(Length::Fill),
+        button("Remove")
+            .on_press(Message::Remove(kind, index))
+            .style(style::button::hyperlink_inverted),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(5)
+    .into()
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    #[default]
+    Include,
+    Exclude,
+}
+
+impl Kind {
+    const ALL: &'static [Self] = &[Self::Include, Self::Exclude];
+
+    fn list(self, filter: &Regex) -> &Vec<String> {
+        match self {
+            Kind::Include => &filter.include,
+            Kind::Exclude => &filter.exclude,
+        }
+    }
+
+    fn list_mut(self, filter: &mut Regex) -> &mut Vec<String> {
+        match self {
+            Kind::Include => &mut filter.include,
+            Kind::Exclude => &mut filter.exclude,
+        }
+    }
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Kind::Include => "Include",
+                Kind::Exclude => "Exclude",
+            }
+        )
+    }
+}
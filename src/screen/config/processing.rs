@@ -0,0 +1,153 @@
+//! Configure how samples are processed before they're exported
+
+use data::config::processing::{BitDepth, Channels, Normalize};
+use data::config::ProcessingConfig;
+
+use crate::widget::helpers::{control, labelled_picklist};
+use crate::widget::Element;
+
+use iced::widget::{checkbox, column, horizontal_rule};
+use iced::Task;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    RemoveDcOffset(bool),
+    TrimSilence(bool),
+    SilenceThreshold(Decibels),
+    Channels(Channels),
+    Normalize(Normalize),
+    NormalizeLevel(Decibels),
+    FadeIn(bool),
+    FadeOut(bool),
+    FadeLength(Milliseconds),
+    SampleRate(SampleRate),
+    BitDepth(BitDepth),
+    Dither(bool),
+}
+
+pub fn update(cfg: &mut ProcessingConfig, message: Message) -> Task<Message> {
+    tracing::info!("{:?}", &message);
+
+    match message {
+        Message::RemoveDcOffset(toggle) => cfg.remove_dc_offset = toggle,
+        Message::TrimSilence(toggle) => cfg.trim_silence = toggle,
+        Message::SilenceThreshold(Decibels(db)) => cfg.silence_threshold = db,
+        Message::Channels(channels) => cfg.channels = channels,
+        Message::Normalize(normalize) => cfg.normalize = normalize,
+        Message::NormalizeLevel(Decibels(db)) => cfg.normalize_level = db,
+        Message::FadeIn(toggle) => cfg.fade_in = toggle,
+        Message::FadeOut(toggle) => cfg.fade_out = toggle,
+        Message::FadeLength(Milliseconds(ms)) => cfg.fade_length = ms,
+        Message::SampleRate(SampleRate(rate)) => cfg.sample_rate = rate,
+        Message::BitDepth(depth) => cfg.bit_depth = depth,
+        Message::Dither(toggle) => cfg.dither = toggle,
+    }
+    Task::none()
+}
+
+pub fn view(processing: &ProcessingConfig) -> Element<Message> {
+    let silence_threshold = labelled_picklist(
+        "Silence Threshold",
+        [-40, -50, -60, -70, -80, -90].map(Decibels).to_vec(),
+        Some(Decibels(processing.silence_threshold)),
+        Message::SilenceThreshold,
+    );
+
+    let normalize = labelled_picklist(
+        "Normalize",
+        Normalize::ALL,
+        Some(processing.normalize),
+        Message::Normalize,
+    );
+
+    let normalize_level = labelled_picklist(
+        "Normalize Level",
+        [0, -1, -3, -6, -12, -18, -24].map(Decibels).to_vec(),
+        Some(Decibels(processing.normalize_level)),
+        Message::NormalizeLevel,
+    );
+
+    let channels = labelled_picklist(
+        "Channels",
+        Channels::ALL,
+        Some(processing.channels),
+        Message::Channels,
+    );
+
+    let fade_length = labelled_picklist(
+        "Fade Length",
+        [1, 5, 10, 20, 50, 100, 250, 500].map(Milliseconds).to_vec(),
+        Some(Milliseconds(processing.fade_length)),
+        Message::FadeLength,
+    );
+
+    let options = [0, 8000, 11025, 16000, 22050, 32000, 44100, 48000, 96000].map(SampleRate);
+    let sample_rate = labelled_picklist(
+        "Sample Rate",
+        options.to_vec(),
+        Some(SampleRate(processing.sample_rate)),
+        Message::SampleRate,
+    );
+
+    let bit_depth = labelled_picklist(
+        "Bit Depth",
+        BitDepth::ALL,
+        Some(processing.bit_depth),
+        Message::BitDepth,
+    );
+
+    let settings = column![
+        checkbox("Remove DC Offset", processing.remove_dc_offset)
+            .on_toggle(Message::RemoveDcOffset),
+        checkbox("Trim Silence", processing.trim_silence).on_toggle(Message::TrimSilence),
+        silence_threshold,
+        horizontal_rule(1),
+        normalize,
+        normalize_level,
+        channels,
+        horizontal_rule(1),
+        checkbox("Fade In", processing.fade_in).on_toggle(Message::FadeIn),
+        checkbox("Fade Out", processing.fade_out).on_toggle(Message::FadeOut),
+        fade_length,
+        horizontal_rule(1),
+        sample_rate,
+        bit_depth,
+        checkbox("Dither", processing.dither).on_toggle(Message::Dither),
+    ]
+    .spacing(8);
+
+    control("Processing", settings).into()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq)]
+#[repr(transparent)]
+pub struct Decibels(pub i8);
+
+impl std::fmt::Display for Decibels {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} dB", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq)]
+#[repr(transparent)]
+pub struct Milliseconds(pub u16);
+
+impl std::fmt::Display for Milliseconds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ms", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq)]
+#[repr(transparent)]
+pub struct SampleRate(pub u32);
+
+impl std::fmt::Display for SampleRate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "Keep"),
+            n => write!(f, "{} Hz", n),
+        }
+    }
+}
@@ -1,11 +1,13 @@
 //! Configure how samples should be named
 
+use data::config::sample_naming::Field;
 use data::config::SampleNameConfig;
 
+use crate::style;
 use crate::widget::helpers::{centered_column_x, control, labelled_picklist};
 use crate::widget::{helpers::centered_text, Element};
 
-use iced::widget::{checkbox, column, horizontal_rule, row};
+use iced::widget::{checkbox, column, horizontal_rule, row, text, text_input};
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -16,6 +18,8 @@ pub enum Message {
     IndexPadding(u8),
     PreferFilename(bool),
     PrefixSamples(bool),
+    UseTemplate(bool),
+    Template(String),
 }
 
 pub fn update(cfg: &mut SampleNameConfig, message: Message) {
@@ -57,6 +61,8 @@ pub fn update(cfg: &mut SampleNameConfig, message: Message) {
         }
         Message::IndexPadding(padding) => cfg.index_padding = padding,
         Message::PrefixSamples(prefix) => cfg.prefix = prefix,
+        Message::UseTemplate(use_template) => cfg.use_template = use_template,
+        Message::Template(template) => cfg.template = template,
     }
 }
 
@@ -83,14 +89,42 @@ pub fn view(config: &SampleNameConfig, preview: impl ToString) -> Element<Messag
         Message::IndexPadding,
     );
 
+    let template = config.use_template.then(|| {
+        let placeholders = Field::ALL
+            .iter()
+            .map(|field| format!("{{{}}}", field.key()))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        column![
+            text_input("{index:02} - {name|filename}.{ext}", &config.template)
+                .on_input(Message::Template)
+                .padding(8)
+        ]
+        .push(text(format!("Placeholders: {placeholders}")).size(12))
+        .spacing(8)
+    });
+
+    // Nothing can be ripped with an invalid template, so show why instead
+    let preview = match config.validate() {
+        Ok(()) => centered_text(preview.to_string()),
+        Err(error) => centered_text(error).style(style::text::error),
+    };
+
+    let settings = match config.use_template {
+        true => column![],
+        false => column![checkboxes, idx_padding].spacing(8),
+    };
+
     control(
         "Sample Naming",
         column![
-            checkboxes,
-            idx_padding,
-            horizontal_rule(1),
-            centered_column_x(column![centered_text(preview.to_string())])
+            checkbox("Use Template", config.use_template).on_toggle(Message::UseTemplate),
+            settings,
         ]
+        .push_maybe(template)
+        .push(horizontal_rule(1))
+        .push(centered_column_x(column![preview]))
         .spacing(8),
     )
     .into()
@@ -2,8 +2,9 @@
 
 use std::path::{Path, PathBuf};
 
+use data::config::sample_ripping::{Collision, Dedup, Noise};
 use data::config::SampleRippingConfig;
-use xmodits_lib::export::Format;
+use data::ExportFormat;
 
 use crate::style;
 use crate::utils::folder_dialog;
@@ -24,9 +25,17 @@ pub fn destination_is_valid(ripping: &SampleRippingConfig) -> bool {
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    ExportFormat(Format),
+    ExportFormat(ExportFormat),
     SelfContained(bool),
     StrictLoad(bool),
+    Sanitize(bool),
+    EmbedTags(bool),
+    Sfz(bool),
+    SoundFont(bool),
+    Manifest(bool),
+    Collision(Collision),
+    Dedup(Dedup),
+    Noise(Noise),
     WorkerThreads(Workers),
     FolderDepth(u8),
     Destination(Option<PathBuf>),
@@ -41,6 +50,14 @@ pub fn update(cfg: &mut SampleRippingConfig, message: Message) -> Task<Message>
         Message::SelfContained(toggle) => cfg.self_contained = toggle,
         Message::FolderDepth(depth) => cfg.folder_max_depth = depth,
         Message::StrictLoad(strict) => cfg.strict = strict,
+        Message::Sanitize(sanitize) => cfg.sanitize = sanitize,
+        Message::EmbedTags(embed) => cfg.embed_tags = embed,
+        Message::Sfz(sfz) => cfg.sfz = sfz,
+        Message::SoundFont(soundfont) => cfg.soundfont = soundfont,
+        Message::Manifest(manifest) => cfg.manifest = manifest,
+        Message::Collision(collision) => cfg.collision = collision,
+        Message::Dedup(dedup) => cfg.dedup = dedup,
+        Message::Noise(noise) => cfg.noise = noise,
         Message::WorkerThreads(Workers(threads)) => cfg.worker_threads = threads,
         Message::Destination(destination) => {
             if let Some(destination) = destination {
@@ -80,6 +97,11 @@ pub fn view(ripping: &SampleRippingConfig) -> Element<Message> {
     let col1 = column![
         checkbox("Self Contained", ripping.self_contained).on_toggle(Message::SelfContained),
         checkbox("Strict Loading", ripping.strict).on_toggle(Message::StrictLoad),
+        checkbox("Sanitize Names", ripping.sanitize).on_toggle(Message::Sanitize),
+        checkbox("Embed Metadata", ripping.embed_tags).on_toggle(Message::EmbedTags),
+        checkbox("Generate SFZ", ripping.sfz).on_toggle(Message::Sfz),
+        checkbox("Generate SoundFont", ripping.soundfont).on_toggle(Message::SoundFont),
+        checkbox("Write Manifest", ripping.manifest).on_toggle(Message::Manifest),
     ]
     .spacing(8);
 
@@ -90,6 +112,27 @@ pub fn view(ripping: &SampleRippingConfig) -> Element<Message> {
         Message::ExportFormat,
     );
 
+    let collision = labelled_picklist(
+        "If a Sample Exists",
+        Collision::ALL,
+        Some(ripping.collision),
+        Message::Collision,
+    );
+
+    let dedup = labelled_picklist(
+        "Duplicate Samples",
+        Dedup::ALL,
+        Some(ripping.dedup),
+        Message::Dedup,
+    );
+
+    let noise = labelled_picklist(
+        "Static Noise",
+        Noise::ALL,
+        Some(ripping.noise),
+        Message::Noise,
+    );
+
     let folder_scan_depth = labelled_picklist(
         "Folder Scan Depth",
         [1, 2, 3, 4, 5, 6, 7].as_slice(),
@@ -108,6 +151,9 @@ pub fn view(ripping: &SampleRippingConfig) -> Element<Message> {
     let settings = column![
         col1,
         export_format,
+        collision,
+        dedup,
+        noise,
         horizontal_rule(1),
         folder_scan_depth,
         worker_threads,
@@ -0,0 +1,129 @@
+//! Previous rips
+
+use std::path::PathBuf;
+
+use data::history::{Outcome, Record};
+use data::History;
+use iced::widget::{column, container, row, scrollable, text, Space};
+use iced::{Alignment, Length};
+
+use crate::ripper;
+use crate::style;
+use crate::widget::helpers::{action, centered_container, control_filled};
+use crate::widget::Element;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Rip the same entries with the same configuration
+    Rerun(usize),
+    Open(PathBuf),
+    Remove(usize),
+    Clear,
+}
+
+/// Returns the record that should be ripped again
+pub fn update(history: &mut History, msg: Message) -> Option<Record> {
+    match msg {
+        Message::Rerun(index) => return history.records.get(index).cloned(),
+        Message::Open(path) => {
+            if let Err(e) = open::that_detached(path) {
+                tracing::warn!("Could not open path: {}", e);
+            }
+        }
+        Message::Remove(index) => {
+            // Headless mode could have added records since, so find it by its date
+            let date = history.records.get(index)?.date;
+
+            modify(history, |history| {
+                if let Some(index) = history.records.iter().position(|r| r.date == date) {
+                    history.remove(index);
+                }
+            });
+        }
+        Message::Clear => modify(history, History::clear),
+    }
+    None
+}
+
+/// Change the saved history, and show the latest one
+fn modify(history: &mut History, change: impl FnOnce(&mut History)) {
+    match ripper::history::modify(change) {
+        Ok(latest) => *history = latest,
+        Err(e) => tracing::error!("Could not save the rip history: {}", e),
+    }
+}
+
+pub fn view(history: &History, ripping: bool) -> Element<Message> {
+    let content: Element<Message> = match history.is_empty() {
+        true => centered_container(text("Nothing has been ripped yet...")).into(),
+        false => {
+            let records = history
+                .records
+                .iter()
+                .enumerate()
+                .rev()
+                .map(|(index, record)| view_record(index, record, ripping));
+
+            scrollable(column(records).spacing(8).padding(4))
+                .height(Length::Fill)
+                .into()
+        }
+    };
+
+    let title = row![
+        text("History"),
+        Space::with_width(Length::Fill),
+        action("Clear", (!history.is_empty()).then_some(Message::Clear))
+            .style(style::button::cancel)
+    ]
+    .align_y(Alignment::Center);
+
+    control_filled(title, content).into()
+}
+
+fn view_record(index: usize, record: &Record, ripping: bool) -> Element<Message> {
+    let outcome = text(record.outcome.to_string()).style(match record.outcome {
+        Outcome::NoErrors => style::text::primary,
+        Outcome::Cancelled => style::text::warning,
+        Outcome::Aborted | Outcome::Errors { .. } => style::text::error,
+    });
+
+    let summary = row![
+        text(record.date.format("%Y-%m-%d %H:%M").to_string()),
+        Space::with_width(Length::Fill),
+        outcome
+    ]
+    .align_y(Alignment::Center);
+
+    let details = text(format!(
+        "Entries: {}, {}",
+        record.entries.len(),
+        record.time()
+    ));
+
+    let destination = text(record.destination.display().to_string());
+
+    let buttons = row![
+        action("Rip Again", (!ripping).then_some(Message::Rerun(index)))
+            .style(style::button::start),
+        action(
+            "Open Destination",
+            Some(Message::Open(record.destination.clone()))
+        ),
+    ]
+    .push_maybe(record.error_log.as_ref().map(|log| {
+        action(
+            "Open Errors",
+            log.exists().then(|| Message::Open(log.clone())),
+        )
+    }))
+    .push(Space::with_width(Length::Fill))
+    .push(action("Remove", Some(Message::Remove(index))).style(style::button::cancel))
+    .spacing(5);
+
+    container(column![summary, details, destination, buttons].spacing(6))
+        .padding(8)
+        .width(Length::Fill)
+        .style(style::container::black)
+        .into()
+}
@@ -27,6 +27,8 @@ pub enum RippingState {
         message: Option<String>,
         progress: f32,
         errors: u64,
+        /// The workers will wait until the rip is resumed
+        paused: bool,
     },
     /// The application has finished ripping samples
     Finished {
@@ -57,6 +59,12 @@ impl RippingState {
         self.update_message(Some(message.into()))
     }
 
+    pub fn set_paused(&mut self, new_paused: bool) {
+        if let Self::Ripping { paused, .. } = self {
+            *paused = new_paused
+        }
+    }
+
     pub fn is_ripping(&self) -> bool {
         matches!(self, Self::Ripping { .. })
     }
@@ -90,6 +98,7 @@ pub fn view_ripping(
     message: &Option<String>,
     progress: f32,
     total_errors: u64,
+    paused: bool,
     show_gif: bool,
 ) -> Element<Message> {
     let cancel_ripping_button = button("CANCEL")
@@ -97,11 +106,24 @@ pub fn view_ripping(
         .style(style::button::cancel)
         .padding(5);
 
+    let pause_button = match paused {
+        true => button("RESUME")
+            .on_press(Message::Resume)
+            .style(style::button::start),
+        false => button("PAUSE").on_press(Message::Pause),
+    }
+    .padding(5);
+
+    let message = match paused {
+        true => "Paused",
+        false => message.as_deref().unwrap_or("Ripping..."),
+    };
+
     let view = column![
-        text(message.as_deref().unwrap_or("Ripping...")),
+        text(message),
         text(format!("{}% - Errors: {}", progress.floor(), total_errors)),
         progress_bar(0.0..=100.0, progress).height(5).width(200),
-        cancel_ripping_button,
+        row![pause_button, cancel_ripping_button].spacing(8),
     ]
     .push_maybe(show_gif.then(|| widget::animation::GIF.ripping()).flatten())
     .spacing(8)
@@ -9,8 +9,10 @@ pub use preview_manager::*;
 #[cfg(not(feature = "audio"))]
 pub mod preview_manager_dummy {
     use crate::screen::entry::Entries;
+    use data::config::{SampleNameConfig, SampleRippingConfig};
 
     use iced::{window::Id, Task};
+    use std::collections::BTreeSet;
     use std::path::PathBuf;
 
     #[derive(Clone, Copy, Debug)]
@@ -20,9 +22,18 @@ pub mod preview_manager_dummy {
     pub struct SamplePreview;
 
     impl SamplePreview {
-        pub fn update(&mut self, _msg: Message, _entries: &mut Entries) -> Task<Message> {
+        pub fn update(
+            &mut self,
+            _msg: Message,
+            _entries: &mut Entries,
+            _ripping: &SampleRippingConfig,
+            _naming: &SampleNameConfig,
+        ) -> Task<Message> {
             Task::none()
         }
+        pub fn rip_request(&self, _msg: &Message) -> Option<(PathBuf, BTreeSet<usize>)> {
+            None
+        }
         pub fn load_samples(&self, _id: Id, _path: PathBuf) -> Task<Message> {
             Task::none()
         }
@@ -1,15 +1,21 @@
+mod export;
 mod sample;
 
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use audio_engine::{PlayerHandle, TrackerSample};
+use data::analysis;
+use data::config::sample_ripping::Collision;
+use data::config::{SampleNameConfig, SampleRippingConfig};
 use iced::widget::{button, checkbox, column, progress_bar, row, scrollable, slider, text, Space};
 use iced::{task, Alignment, Task, Length};
 
+use crate::ripper::extraction::wrapper;
 use crate::screen::entry::Entries;
-use crate::utils::filename;
-use crate::widget::helpers::{centered_container, fill_container, warning};
+use crate::utils::{create_file_dialog, filename, folder_dialog};
+use crate::widget::helpers::{action, centered_container, fill_container, warning};
 use crate::widget::waveform_view::{Marker, WaveData};
 use crate::widget::{Button, Container, Element, Row, WaveformViewer};
 use crate::{icon, style};
@@ -29,7 +35,18 @@ pub enum Message {
     SetVolume(f32),
     AddEntry(PathBuf),
     Loaded(Result<SamplePack, (PathBuf, String)>),
+    /// Which samples of a module are most likely static noise
+    Analysed(PathBuf, Vec<bool>),
     Progress(Option<f32>),
+    Check(usize, bool),
+    CheckAll(bool),
+    ExportSample,
+    ExportChecked,
+    SaveSample(usize, Option<PathBuf>),
+    SaveChecked(Option<PathBuf>),
+    Exported(Result<String, String>),
+    /// Handled by the main window
+    RipChecked,
 }
 
 /// The state of the sample player
@@ -44,6 +61,8 @@ pub enum State {
     Loaded {
         selected: Option<usize>,
         samples: SamplePack,
+        /// Samples that are checked to be exported
+        checked: BTreeSet<usize>,
     },
 }
 
@@ -71,6 +90,8 @@ pub struct Instance {
     settings: MediaSettings,
     pub hovered: bool,
     progress: Option<f32>,
+    /// The result of the last export
+    exported: Option<Result<String, String>>,
 }
 
 impl Instance {
@@ -88,6 +109,7 @@ impl Instance {
             settings: MediaSettings::default(),
             hovered: false,
             progress: None,
+            exported: None,
         }
     }
 
@@ -96,7 +118,13 @@ impl Instance {
         self
     }
 
-    pub fn update(&mut self, message: Message, entries: &mut Entries) -> Task<Message> {
+    pub fn update(
+        &mut self,
+        message: Message,
+        entries: &mut Entries,
+        ripping: &SampleRippingConfig,
+        naming: &SampleNameConfig,
+    ) -> Task<Message> {
         match message {
             Message::Select(index) => {
                 if let State::Loaded { selected, .. } = &mut self.state {
@@ -114,12 +142,27 @@ impl Instance {
             Message::SetPlayOnSelection(toggle) => self.settings.play_on_selection = toggle,
             Message::AddEntry(path) => entries.add(path),
             Message::Loaded(result) => {
+                let task = match &result {
+                    Ok(samples) => analyse_samples(samples),
+                    Err(_) => Task::none(),
+                };
+
                 self.state = match result {
                     Ok(samples) => State::Loaded {
                         selected: None,
                         samples,
+                        checked: BTreeSet::new(),
                     },
                     Err((path, reason)) => State::Failed { path, reason },
+                };
+
+                return task;
+            }
+            Message::Analysed(path, noise) => {
+                if let State::Loaded { samples, .. } = &mut self.state {
+                    if samples.path() == path {
+                        samples.set_noise(&noise);
+                    }
                 }
             }
             Message::SetVolume(volume) => {
@@ -127,10 +170,171 @@ impl Instance {
                 self.settings.volume = volume;
             }
             Message::Progress(p) => self.progress = p,
+            Message::Check(index, check) => {
+                if let State::Loaded { checked, .. } = &mut self.state {
+                    match check {
+                        true => checked.insert(index),
+                        false => checked.remove(&index),
+                    };
+                }
+            }
+            Message::CheckAll(check) => {
+                if let State::Loaded {
+                    checked, samples, ..
+                } = &mut self.state
+                {
+                    *checked = match check {
+                        true => (0..samples.inner().len())
+                            .filter(|index| !samples.inner()[*index].is_invalid())
+                            .collect(),
+                        false => BTreeSet::new(),
+                    };
+                }
+            }
+            Message::ExportSample => {
+                if let State::Loaded {
+                    selected: Some(index),
+                    samples,
+                    ..
+                } = &self.state
+                {
+                    let index = *index;
+
+                    if let Some((_, name)) = export::names(samples, [index], ripping, naming).pop()
+                    {
+                        let name = export::file_name(&name);
+                        return Task::perform(create_file_dialog(name), move |path| {
+                            Message::SaveSample(index, path)
+                        });
+                    }
+                }
+            }
+            Message::ExportChecked => {
+                if self.total_checked() > 0 {
+                    return Task::perform(folder_dialog(), Message::SaveChecked);
+                }
+            }
+            Message::SaveSample(index, Some(path)) => {
+                // The save dialog already asked to replace the file
+                let batch = self.batch([(index, path)], ripping, Collision::Overwrite);
+                return self.export(batch);
+            }
+            Message::SaveChecked(Some(folder)) => {
+                if let State::Loaded {
+                    checked, samples, ..
+                } = &self.state
+                {
+                    let items: Vec<(usize, PathBuf)> =
+                        export::names(samples, checked.iter().copied(), ripping, naming)
+                            .into_iter()
+                            .map(|(index, name)| (index, folder.join(name)))
+                            .collect();
+
+                    let batch = self.batch(items, ripping, ripping.collision);
+                    return self.export(batch);
+                }
+            }
+            Message::SaveSample(_, None) | Message::SaveChecked(None) => (),
+            Message::RipChecked => (),
+            Message::Exported(result) => {
+                match &result {
+                    Ok(summary) => tracing::info!("{summary}"),
+                    Err(error) => tracing::error!("{error}"),
+                }
+                self.exported = Some(result);
+            }
         }
         Task::none()
     }
 
+    fn selected(&self) -> Option<usize> {
+        match &self.state {
+            State::Loaded { selected, .. } => *selected,
+            _ => None,
+        }
+    }
+
+    /// How many samples can be exported
+    fn total_valid(&self) -> usize {
+        match &self.state {
+            State::Loaded { samples, .. } => samples
+                .inner()
+                .iter()
+                .filter(|result| !result.is_invalid())
+                .count(),
+            _ => 0,
+        }
+    }
+
+    fn total_checked(&self) -> usize {
+        match &self.state {
+            State::Loaded { checked, .. } => checked.len(),
+            _ => 0,
+        }
+    }
+
+    /// The module, and the internal index of every checked sample
+    pub fn checked_samples(&self) -> Option<(PathBuf, BTreeSet<usize>)> {
+        let State::Loaded {
+            samples, checked, ..
+        } = &self.state
+        else {
+            return None;
+        };
+
+        let indexes: BTreeSet<usize> = checked
+            .iter()
+            .filter_map(|index| samples.inner().get(*index)?.metadata())
+            .map(|smp| smp.index_raw() as usize)
+            .collect();
+
+        (!indexes.is_empty()).then(|| (samples.path().to_owned(), indexes))
+    }
+
+    /// Collect the samples to export, along with where they should be written
+    fn batch(
+        &self,
+        items: impl IntoIterator<Item = (usize, PathBuf)>,
+        ripping: &SampleRippingConfig,
+        collision: Collision,
+    ) -> Option<export::Batch> {
+        let State::Loaded { samples, .. } = &self.state else {
+            return None;
+        };
+
+        let items = items
+            .into_iter()
+            .filter_map(|(index, path)| {
+                let result = samples.inner().get(index)?;
+
+                Some(export::Item {
+                    metadata: result.metadata()?.clone(),
+                    buffer: result.tracker_sample()?,
+                    path,
+                })
+            })
+            .collect();
+
+        Some(export::Batch {
+            items,
+            module: samples.name().to_owned(),
+            format: samples.format().to_owned(),
+            source: samples.path().to_owned(),
+            ripping: ripping.clone(),
+            collision,
+        })
+    }
+
+    fn export(&mut self, batch: Option<export::Batch>) -> Task<Message> {
+        match batch {
+            Some(batch) => {
+                self.exported = None;
+                Task::perform(export::export(batch), Message::Exported)
+            }
+            None => Task::none(),
+        }
+    }
+
     pub fn view(&self, entries: &Entries) -> Element<Message> {
         let info = fill_container(self.view_sample_info())
             .padding(8)
@@ -167,7 +371,33 @@ impl Instance {
             .padding(8)
             .style(style::container::black);
 
-        let top_right = column![sample_list, top_right_controls]
+        let export_controls = {
+            let selected = self.selected().is_some();
+            let checked = self.total_checked();
+            let loaded = self.loaded_path().is_some();
+
+            let check_all = checkbox("Check All", checked > 0 && checked == self.total_valid())
+                .on_toggle_maybe(loaded.then_some(Message::CheckAll))
+                .style(style::checkbox::inverted);
+
+            row![
+                check_all,
+                Space::with_width(Length::Fill),
+                action(
+                    "Export Sample...",
+                    selected.then_some(Message::ExportSample)
+                ),
+                action(
+                    text(format!("Export Checked ({checked})...")),
+                    (checked > 0).then_some(Message::ExportChecked)
+                ),
+                action("Rip Checked", (checked > 0).then_some(Message::RipChecked)),
+            ]
+            .spacing(5)
+            .align_y(Alignment::Center)
+        };
+
+        let top_right = column![sample_list, export_controls, top_right_controls]
             .spacing(5)
             .width(Length::Fill);
 
@@ -184,10 +414,15 @@ impl Instance {
         });
 
         let static_noise_warning = warning(
-            || false,
+            || self.selected_is_noise(),
             "WARNING - This sample is most likely static noise.",
         );
 
+        let exported = self.exported.as_ref().map(|result| match result {
+            Ok(summary) => text(summary),
+            Err(error) => text(error).style(style::text::warning),
+        });
+
         let no_audio_warning = warning(
             || self.player.is_inactive(),
             "WARNING - Could not connect to audio device. No sound will be played.",
@@ -200,6 +435,7 @@ impl Instance {
         let main = column![top_half, waveform_viewer]
             .push_maybe(progress)
             .push_maybe(static_noise_warning)
+            .push_maybe(exported)
             .push_maybe(no_audio_warning)
             .spacing(5);
 
@@ -263,6 +499,17 @@ impl Instance {
         }
     }
 
+    fn selected_is_noise(&self) -> bool {
+        match &self.state {
+            State::Loaded {
+                selected: Some(index),
+                samples,
+                ..
+            } => samples.is_noise(*index),
+            _ => false,
+        }
+    }
+
     pub fn loaded_path(&self) -> Option<&Path> {
         match &self.state {
             State::Loaded { samples, .. } => Some(samples.path()),
@@ -291,16 +538,14 @@ impl Instance {
             State::None => centered_container("Drag and drop a module to preview").into(),
             State::Loading => centered_container("Loading...").into(),
             State::Failed { .. } => centered_container("ERROR").into(),
-            State::Loaded { samples, .. } => match samples.is_empty() {
+            State::Loaded {
+                samples, checked, ..
+            } => match samples.is_empty() {
                 true => centered_container("This module doesn't have any samples! o_0").into(),
                 false => scrollable(
-                    column(
-                        samples
-                            .inner()
-                            .iter()
-                            .enumerate()
-                            .map(|(index, result)| result.view_sample(index)),
-                    )
+                    column(samples.inner().iter().enumerate().map(|(index, result)| {
+                        result.view_sample(index, checked.contains(&index))
+                    }))
                     .spacing(10)
                     .padding(4),
                 )
@@ -422,9 +667,10 @@ fn load_samples(path: PathBuf) -> Task<Message> {
                         return Err(Error::io_error("File size exceeds 40 MB").unwrap_err());
                     }
 
-                    let module = xmodits_lib::load(&mut file, Some(path.to_owned()))?;
+                    let module = wrapper::load(&mut file, path)?;
                     let sample_pack = audio_engine::SamplePack::build(&module);
                     let name = sample_pack.name;
+                    let format = sample_pack.format;
 
                     let samples = sample_pack
                         .samples
@@ -437,13 +683,14 @@ fn load_samples(path: PathBuf) -> Task<Message> {
                                     metadata,
                                     buffer,
                                     waveform,
+                                    noise: false,
                                 }
                             }
                             Err(error) => SampleResult::Invalid(error.to_string()),
                         })
                         .collect();
 
-                    Ok(SamplePack::new(name, path.to_owned(), samples))
+                    Ok(SamplePack::new(name, format, path.to_owned(), samples))
                 })
             };
 
@@ -457,3 +704,35 @@ fn load_samples(path: PathBuf) -> Task<Message> {
         Message::Loaded,
     )
 }
+
+/// Check which samples are most likely static noise.
+///
+/// This is done after the samples are loaded so that they can be previewed straight away.
+fn analyse_samples(samples: &SamplePack) -> Task<Message> {
+    let path = samples.path().to_owned();
+    let buffers: Vec<Option<TrackerSample>> = samples
+        .inner()
+        .iter()
+        .map(SampleResult::tracker_sample)
+        .collect();
+
+    Task::perform(
+        async move {
+            let task = move || {
+                buffers
+                    .iter()
+                    .map(|buffer| {
+                        buffer
+                            .as_ref()
+                            .is_some_and(|sample| analysis::is_noise(&sample.buf.buf))
+                    })
+                    .collect()
+            };
+
+            let noise: Vec<bool> = tokio::task::spawn_blocking(task).await.unwrap_or_default();
+
+            (path, noise)
+        },
+        |(path, noise)| Message::Analysed(path, noise),
+    )
+}
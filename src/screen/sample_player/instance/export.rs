@@ -0,0 +1,137 @@
+//! Export samples straight from the sample player, without ripping the whole module.
+//!
+//! Samples are written from the buffers that were decoded for playback,
+//! using the same export format, processing and tags as a normal rip.
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use audio_engine::{Sample, TrackerSample};
+use data::config::sample_ripping::Collision;
+use data::config::{SampleNameConfig, SampleRippingConfig};
+use data::export::process;
+use xmodits_lib::export::name::Context;
+
+use super::sample::SamplePack;
+use crate::ripper::extraction::export::{export_loaded, Origin};
+use crate::ripper::extraction::sanitize::sanitize;
+
+/// A sample that's ready to be exported
+pub struct Item {
+    pub metadata: Sample,
+    pub buffer: TrackerSample,
+    pub path: PathBuf,
+}
+
+/// Samples that are exported together, along with the module they came from
+pub struct Batch {
+    pub items: Vec<Item>,
+    pub module: String,
+    pub format: String,
+    pub source: PathBuf,
+    pub ripping: SampleRippingConfig,
+    pub collision: Collision,
+}
+
+/// Name the samples the same way they'd be named if the module was ripped.
+///
+/// Invalid samples are left out.
+pub fn names(
+    samples: &SamplePack,
+    indexes: impl IntoIterator<Item = usize>,
+    ripping: &SampleRippingConfig,
+    naming: &SampleNameConfig,
+) -> Vec<(usize, String)> {
+    let namer = naming.build_func();
+    let formatter = ripping.exported_format.get_impl();
+
+    let context = Context {
+        total: samples.inner().len(),
+        extension: formatter.extension(),
+        highest: samples
+            .inner()
+            .iter()
+            .filter_map(|result| result.metadata())
+            .map(|smp| smp.index_raw() as usize)
+            .max()
+            .unwrap_or_default(),
+        source_path: Some(samples.path()),
+    };
+
+    indexes
+        .into_iter()
+        .filter_map(|index| {
+            let smp = samples.inner().get(index)?.metadata()?;
+            let name = namer(smp, &context, index);
+
+            Some(match ripping.sanitize {
+                true => (index, sanitize(&name)),
+                false => (index, name),
+            })
+        })
+        .collect()
+}
+
+/// The name of a sample without any folders, so it can be used in a save dialog.
+pub fn file_name(name: &str) -> String {
+    Path::new(name)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| name.to_owned())
+}
+
+/// Export every sample in the batch.
+///
+/// Returns a summary of what was exported, or why it failed.
+pub async fn export(batch: Batch) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || run(batch))
+        .await
+        .map_err(|error| error.to_string())?
+}
+
+fn run(batch: Batch) -> Result<String, String> {
+    let origin = Origin {
+        module: &batch.module,
+        format: &batch.format,
+        source: &batch.source,
+    };
+
+    let mut exported: Vec<PathBuf> = Vec::new();
+    let mut skipped = 0;
+    let mut errors: Vec<String> = Vec::new();
+
+    for item in &batch.items {
+        let (smp, pcm) = process::encode_buffer(&item.metadata, &item.buffer.buf.buf);
+
+        match export_loaded(
+            &smp,
+            Cow::Owned(pcm),
+            &item.path,
+            &origin,
+            &batch.ripping,
+            batch.collision,
+        ) {
+            Ok(Some(path)) => exported.push(path),
+            Ok(None) => skipped += 1,
+            Err(error) => errors.push(format!("{}: {error}", item.path.display())),
+        }
+    }
+
+    if let Some(error) = errors.first() {
+        return Err(match errors.len() {
+            1 => format!("Could not export {error}"),
+            n => format!("Could not export {n} samples, e.g. {error}"),
+        });
+    }
+
+    let skipped = match skipped {
+        0 => String::new(),
+        n => format!(" ({n} skipped, the files already exist)"),
+    };
+
+    Ok(match exported.as_slice() {
+        [] => format!("Nothing was exported{skipped}"),
+        [path] => format!("Exported {}{skipped}", path.display()),
+        paths => format!("Exported {} samples{skipped}", paths.len()),
+    })
+}
@@ -11,7 +11,7 @@ use crate::widget::Element;
 use audio_engine;
 
 use audio_engine::TrackerSample;
-use iced::widget::{button, column, horizontal_rule, row, text, Space};
+use iced::widget::{button, checkbox, column, horizontal_rule, row, text, Space};
 use iced::{Alignment, Length};
 
 use super::Message;
@@ -19,14 +19,16 @@ use super::Message;
 #[derive(Debug, Clone)]
 pub struct SamplePack {
     name: String,
+    format: String,
     path: PathBuf,
     samples: Vec<SampleResult>,
 }
 
 impl SamplePack {
-    pub fn new(name: String, path: PathBuf, samples: Vec<SampleResult>) -> Self {
+    pub fn new(name: String, format: String, path: PathBuf, samples: Vec<SampleResult>) -> Self {
         Self {
             name,
+            format,
             path,
             samples,
         }
@@ -44,6 +46,20 @@ impl SamplePack {
         self.inner()[index].view_sample_info()
     }
 
+    /// Is the sample most likely static noise?
+    pub fn is_noise(&self, index: usize) -> bool {
+        self.inner().get(index).is_some_and(SampleResult::is_noise)
+    }
+
+    /// Flag the samples that are most likely static noise
+    pub fn set_noise(&mut self, flags: &[bool]) {
+        for (sample, flag) in self.samples.iter_mut().zip(flags) {
+            if let SampleResult::Valid { noise, .. } = sample {
+                *noise = *flag;
+            }
+        }
+    }
+
     pub fn tracker_sample(&self, index: usize) -> Option<TrackerSample> {
         self.inner()
             .get(index)
@@ -58,6 +74,11 @@ impl SamplePack {
         &self.name
     }
 
+    /// Tracker format of the module
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
     pub fn is_empty(&self) -> bool {
         self.inner().len() == 0
     }
@@ -70,6 +91,7 @@ pub enum SampleResult {
         metadata: audio_engine::Metadata,
         buffer: TrackerSample,
         waveform: WaveData,
+        noise: bool,
     },
 }
 
@@ -95,6 +117,17 @@ impl SampleResult {
         matches!(self, Self::Invalid(_))
     }
 
+    pub fn is_noise(&self) -> bool {
+        matches!(self, Self::Valid { noise: true, .. })
+    }
+
+    pub fn metadata(&self) -> Option<&audio_engine::Metadata> {
+        match self {
+            SampleResult::Valid { metadata, .. } => Some(metadata),
+            _ => None,
+        }
+    }
+
     pub fn tracker_sample(&self) -> Option<TrackerSample> {
         match &self {
             SampleResult::Valid { buffer, .. } => Some(buffer.clone()),
@@ -102,7 +135,7 @@ impl SampleResult {
         }
     }
 
-    pub fn view_sample(&self, index: usize) -> Element<Message> {
+    pub fn view_sample(&self, index: usize, checked: bool) -> Element<Message> {
         let error_icon = || {
             row![]
                 .push(Space::with_width(Length::Fill))
@@ -115,10 +148,16 @@ impl SampleResult {
                 title if title.is_empty() => format!("{}", index + 1),
                 title => format!("{} - {}", index + 1, title),
             }))
-            .push_maybe(self.is_invalid().then_some(error_icon()))
+            .push_maybe((self.is_invalid() || self.is_noise()).then_some(error_icon()))
             .spacing(5);
 
+        // Invalid samples can't be exported
+        let check = checkbox("", checked).on_toggle_maybe(
+            (!self.is_invalid()).then_some(move |checked| Message::Check(index, checked)),
+        );
+
         row![
+            check,
             button(title)
                 .width(Length::Fill)
                 .style(match self.is_invalid() {
@@ -128,6 +167,7 @@ impl SampleResult {
                 .on_press(Message::Select(index)),
             Space::with_width(15)
         ]
+        .align_y(Alignment::Center)
         .into()
     }
 
@@ -3,7 +3,7 @@ use super::instance::{self, Instance, MediaSettings};
 use iced::window::{self, Id};
 use iced::{Size, Task};
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
 
 use crate::app::application_icon;
@@ -11,6 +11,7 @@ use crate::screen::entry::Entries;
 use crate::widget::Element;
 
 use audio_engine::SamplePlayer;
+use data::config::{SampleNameConfig, SampleRippingConfig};
 
 const WINDOW_SIZE: Size = Size::new(640.0, 500.0);
 
@@ -28,9 +29,15 @@ pub struct SamplePreview {
 }
 
 impl SamplePreview {
-    pub fn update(&mut self, msg: Message, entries: &mut Entries) -> Task<Message> {
+    pub fn update(
+        &mut self,
+        msg: Message,
+        entries: &mut Entries,
+        ripping: &SampleRippingConfig,
+        naming: &SampleNameConfig,
+    ) -> Task<Message> {
         match msg {
-            Message::Window(id, msg) => self.update_window(id, msg, entries),
+            Message::Window(id, msg) => self.update_window(id, msg, entries, ripping, naming),
             Message::WindowOpened(id, path) => {
                 let (instance, load_samples) =
                     Instance::new(self.audio_engine.create_handle(), path);
@@ -43,17 +50,29 @@ impl SamplePreview {
         }
     }
 
+    /// The module and samples to rip, if the message asks to rip the checked samples
+    pub fn rip_request(&self, msg: &Message) -> Option<(PathBuf, BTreeSet<usize>)> {
+        match msg {
+            Message::Window(id, instance::Message::RipChecked) => {
+                self.windows.get(id)?.checked_samples()
+            }
+            _ => None,
+        }
+    }
+
     pub fn update_window(
         &mut self,
         id: Id,
         msg: instance::Message,
         entries: &mut Entries,
+        ripping: &SampleRippingConfig,
+        naming: &SampleNameConfig,
     ) -> Task<Message> {
         // If the window has closed, discard the message
         match self.windows.get_mut(&id) {
             None => Task::none(),
             Some(window) => window
-                .update(msg, entries)
+                .update(msg, entries, ripping, naming)
                 .map(move |msg| Message::Window(id, msg)),
         }
     }
@@ -5,13 +5,14 @@
 use std::path::{Path, PathBuf};
 
 use crate::app::Message;
+use crate::ripper::extraction::{manifest, wrapper};
 use crate::utils::filename;
 use crate::widget::helpers::{centered_container, control_filled, text_adv};
 use crate::widget::Element;
 
 use iced::widget::{column, text, Space};
 use iced::Alignment;
-use xmodits_lib::Info;
+use xmodits_lib::{Error, Info};
 
 #[derive(Default, Debug, Clone)]
 pub enum TrackerInfo {
@@ -93,7 +94,7 @@ impl TrackerInfo {
 
 pub async fn probe(path: PathBuf) -> TrackerInfo {
     tokio::task::spawn_blocking(move || {
-        let result = crate::logger::log_file_on_panic(&path, Info::new);
+        let result = crate::logger::log_file_on_panic(&path, info);
 
         match result {
             Ok(Info {
@@ -117,3 +118,14 @@ pub async fn probe(path: PathBuf) -> TrackerInfo {
     .await
     .unwrap_or_default()
 }
+
+/// Compressed modules have to be unwrapped before they can be inspected.
+fn info(path: &Path) -> Result<Info, Error> {
+    if !wrapper::is_wrapped(path) {
+        return Info::new(path);
+    }
+
+    let module = wrapper::load(&mut std::fs::File::open(path)?, path)?;
+
+    Ok(manifest::info(&module, path))
+}